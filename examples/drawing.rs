@@ -17,8 +17,11 @@ use ggez_planet::{
         rendering::{ImageBuilder, RenderInstruction, Renderable},
         Position, ScreenSize,
     },
-    input::{MouseButtons, MouseMotion, MousePosition, MouseWheel},
-    Planet,
+    input::{
+        cursor::CursorSettings, touch::Gestures, MouseButtons, MouseMotion, MousePosition,
+        MouseWheel,
+    },
+    plugin::{PlanetBuilder, Plugin},
 };
 use std::collections::HashSet;
 
@@ -163,14 +166,18 @@ impl<'a> System<'a> for MousePan {
     type SystemData = (
         Read<'a, MouseMotion>,
         Read<'a, MouseButtons>,
+        Write<'a, CursorSettings>,
         WriteStorage<'a, Position>,
         WriteStorage<'a, Camera>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mouse_motion, mouse_buttons, mut positions, mut cameras) = data;
+        let (mouse_motion, mouse_buttons, mut cursor_settings, mut positions, mut cameras) = data;
+
+        // Keep the cursor out of the way while panning
+        cursor_settings.relative = mouse_buttons.is_down(&MouseButton::Middle);
 
-        if !mouse_buttons.is_down(&MouseButton::Middle) {
+        if !cursor_settings.relative {
             return;
         }
 
@@ -209,6 +216,34 @@ impl<'a> System<'a> for ScrollZoom {
     }
 }
 
+// Camera panning and zooming with two fingers,
+// ggez's own event loop doesn't deliver touches yet so this
+// only kicks in when they're forwarded with `Planet::touch_event`
+struct TouchPanZoom;
+
+// TODO: Only pan and zoom the active camera
+impl<'a> System<'a> for TouchPanZoom {
+    type SystemData = (
+        Read<'a, Gestures>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, Camera>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (gestures, mut positions, mut cameras) = data;
+
+        for (camera, position) in (&mut cameras, &mut positions).join() {
+            if let Some(pan) = gestures.pan {
+                position.0 -= pan / camera.zoom;
+            }
+
+            if let Some(pinch) = gestures.pinch {
+                camera.zoom *= pinch;
+            }
+        }
+    }
+}
+
 pub fn setup<'a, 'b>(
     _ctx: &mut Context,
     world: &mut World,
@@ -220,6 +255,7 @@ pub fn setup<'a, 'b>(
 
     dispatcher_builder.add(MousePan, "mouse_pan", &[]);
     dispatcher_builder.add(ScrollZoom, "scroll_zoom", &[]);
+    dispatcher_builder.add(TouchPanZoom, "touch_pan_zoom", &["gesture_recognizer"]);
 
     world
         .create_entity()
@@ -234,17 +270,30 @@ pub fn setup<'a, 'b>(
         .build();
 }
 
+// Plugins are how everything gets added to a planet, including ggez_planet's own modules
+struct DrawingPlugin;
+
+impl<'a, 'b> Plugin<'a, 'b> for DrawingPlugin {
+    fn build(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+    ) -> GameResult {
+        setup(ctx, world, dispatcher_builder);
+
+        Ok(())
+    }
+}
+
 pub fn main() -> GameResult {
     let cb = ggez::ContextBuilder::new("drawing_with_ggez_planet", "ggez_planet");
     let (ctx, event_loop) = &mut cb.build()?;
 
-    let mut world = specs::World::new();
-    let mut dispatcher_builder = DispatcherBuilder::new();
-
-    // Setup the world and dispatcher for ggez planet to use
-    setup(ctx, &mut world, &mut dispatcher_builder);
-
     // Create Planet, which implements ggez's EventHandler so we can just run it
-    let state = &mut Planet::new(ctx, world, dispatcher_builder);
+    let state = &mut PlanetBuilder::default()
+        .with_default_plugins()
+        .with_plugin(DrawingPlugin)
+        .build(ctx)?;
     event::run(ctx, event_loop, state)
 }
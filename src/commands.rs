@@ -0,0 +1,87 @@
+use crate::{graphics::Images, input::cursor::CursorSettings};
+use ggez::{
+    conf::FullscreenType,
+    graphics::{self, Image},
+    Context, GameResult,
+};
+use specs::prelude::*;
+use std::mem;
+
+#[derive(Clone, Debug)]
+pub enum ContextCommand {
+    /// Loads an image from ggez's resource folders into `Images` under `name`
+    LoadImage {
+        name: String,
+        path: String,
+    },
+    SetWindowTitle(String),
+    SetFullscreen(bool),
+    /// Sets `grabbed` in `CursorSettings`
+    SetCursorGrabbed(bool),
+    /// Sets `visible` in `CursorSettings` to the opposite
+    SetCursorHidden(bool),
+}
+
+/// Things systems can't do themselves because they need the `Context`,
+/// `Planet` runs them in order after every update
+#[derive(Default)]
+pub struct ContextCommands(pub Vec<ContextCommand>);
+
+impl ContextCommands {
+    pub fn push(&mut self, command: ContextCommand) {
+        self.0.push(command);
+    }
+}
+
+/// The commands that failed the last time they were run, along with why
+#[derive(Default)]
+pub struct CommandErrors(pub Vec<(ContextCommand, String)>);
+
+pub fn run_commands(ctx: &mut Context, world: &mut World) {
+    let commands = mem::replace(&mut world.write_resource::<ContextCommands>().0, Vec::new());
+    let mut errors = Vec::new();
+
+    for command in commands {
+        if let Err(err) = run_command(ctx, world, &command) {
+            errors.push((command, err.to_string()));
+        }
+    }
+
+    world.write_resource::<CommandErrors>().0 = errors;
+}
+
+fn run_command(ctx: &mut Context, world: &World, command: &ContextCommand) -> GameResult {
+    use ContextCommand::*;
+
+    match command {
+        LoadImage { name, path } => {
+            let image = Image::new(ctx, path)?;
+            world
+                .write_resource::<Images>()
+                .0
+                .insert(name.clone(), image);
+        }
+        SetWindowTitle(title) => graphics::set_window_title(ctx, title),
+        SetFullscreen(fullscreen) => graphics::set_fullscreen(
+            ctx,
+            if *fullscreen {
+                FullscreenType::Desktop
+            } else {
+                FullscreenType::Windowed
+            },
+        )?,
+        SetCursorGrabbed(grabbed) => world.write_resource::<CursorSettings>().grabbed = *grabbed,
+        SetCursorHidden(hidden) => world.write_resource::<CursorSettings>().visible = !*hidden,
+    }
+
+    Ok(())
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    _dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.add_resource(ContextCommands::default());
+    world.add_resource(CommandErrors::default());
+}
@@ -0,0 +1,355 @@
+use super::{fps_display::FpsDisplay, performance_graph::PerformanceGraph};
+use crate::{
+    graphics::{
+        camera::{ActiveCamera, Camera},
+        ui::{
+            layout::{Direction, Edges, Size, UiContainer, UiParent, UiSize},
+            Hidden, UiElement,
+        },
+        Anchor, Layer,
+    },
+    input::{InputEvent, InputEvents, TimedInputEvent},
+    renderers::{panel::Panel, text::Text},
+};
+use ggez::{
+    graphics::{Color, Scale},
+    input::keyboard::KeyCode,
+    Context,
+};
+use specs::{prelude::*, shred::Resources, shrev::ReaderId};
+use std::{collections::BTreeMap, mem, sync::Arc};
+
+static MAX_LOG_LINES: usize = 200;
+static VISIBLE_LINES: usize = 12;
+static FONT_SIZE: f32 = 16.0;
+static LINE_HEIGHT: f32 = 20.0;
+static PADDING: f32 = 5.0;
+static CONSOLE_LAYER: i32 = 1000;
+
+/// Gets the words typed after the command's name
+pub type ConsoleCommand = Arc<dyn Fn(&mut World, &[&str]) -> Result<String, String> + Send + Sync>;
+
+/// The console toggled with the backtick key, along with the commands that can be typed into it.
+/// While it's open every other system sees no keys, gamepad buttons or mouse buttons, motion
+/// or wheel. `InputEvents` still has everything
+#[derive(Default)]
+pub struct DebugConsole {
+    pub open: bool,
+    input: String,
+    log: Vec<String>,
+    pending: Vec<String>,
+    commands: BTreeMap<String, (String, ConsoleCommand)>,
+}
+
+impl DebugConsole {
+    /// Adds a command, replacing any with the same name.
+    /// What it returns is written to the log, errors included
+    pub fn register<F>(&mut self, name: &str, help: &str, command: F)
+    where
+        F: Fn(&mut World, &[&str]) -> Result<String, String> + Send + Sync + 'static,
+    {
+        self.commands
+            .insert(name.to_owned(), (help.to_owned(), Arc::new(command)));
+    }
+
+    pub fn log<S: Into<String>>(&mut self, line: S) {
+        self.log.extend(line.into().lines().map(str::to_owned));
+
+        if self.log.len() > MAX_LOG_LINES {
+            let overflow = self.log.len() - MAX_LOG_LINES;
+            self.log.drain(..overflow);
+        }
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.log
+    }
+
+    pub fn clear(&mut self) {
+        self.log.clear();
+    }
+
+    /// Queues `line` to run as if it was typed in
+    pub fn run(&mut self, line: &str) {
+        self.pending.push(line.to_owned());
+    }
+
+    fn submit(&mut self) {
+        let line = mem::replace(&mut self.input, String::new());
+
+        self.log(format!("> {}", line));
+        self.run(&line);
+    }
+}
+
+/// Runs the command lines queued since the last update, `Planet` calls this
+/// after the systems have run since commands get the whole `World`
+pub fn run_console_commands(world: &mut World) {
+    let (pending, commands) = {
+        let mut console = world.write_resource::<DebugConsole>();
+
+        if console.pending.is_empty() {
+            return;
+        }
+
+        (
+            mem::replace(&mut console.pending, Vec::new()),
+            console.commands.clone(),
+        )
+    };
+
+    for line in pending {
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        if let Some((name, args)) = words.split_first() {
+            let output = match commands.get(*name) {
+                Some((_, command)) => command(world, args),
+                None => Err(format!("Unknown command {:?}, try help", name)),
+            };
+
+            let mut console = world.write_resource::<DebugConsole>();
+
+            match output {
+                Ok(output) => {
+                    if !output.is_empty() {
+                        console.log(output);
+                    }
+                }
+                Err(err) => console.log(format!("Error: {}", err)),
+            }
+        }
+    }
+}
+
+/// Hides the entities if any of them are showing, otherwise shows them all
+fn toggle_hidden(world: &mut World, entities: Vec<Entity>) -> bool {
+    let mut hiddens = world.write_storage::<Hidden>();
+    let hide = entities.iter().any(|entity| !hiddens.contains(*entity));
+
+    for entity in entities {
+        if hide {
+            let _ = hiddens.insert(entity, Hidden);
+        } else {
+            hiddens.remove(entity);
+        }
+    }
+
+    hide
+}
+
+fn register_builtin_commands(console: &mut DebugConsole) {
+    console.register("help", "Lists every command", |world, _| {
+        let console = world.read_resource::<DebugConsole>();
+
+        Ok(console
+            .commands
+            .iter()
+            .map(|(name, (help, _))| format!("{} - {}", name, help))
+            .collect::<Vec<String>>()
+            .join("\n"))
+    });
+
+    console.register("clear", "Clears the log", |world, _| {
+        world.write_resource::<DebugConsole>().clear();
+
+        Ok(String::new())
+    });
+
+    console.register("set", "set zoom <zoom>", |world, args| match args {
+        ["zoom", zoom] => {
+            let zoom: f32 = zoom
+                .parse()
+                .map_err(|_| format!("{:?} isn't a number", zoom))?;
+            let active_camera = world
+                .read_resource::<ActiveCamera>()
+                .0
+                .ok_or("There's no active camera")?;
+
+            if let Some(camera) = world.write_storage::<Camera>().get_mut(active_camera) {
+                camera.zoom = zoom;
+            }
+
+            Ok(format!("zoom = {}", zoom))
+        }
+        _ => Err("Usage: set zoom <zoom>".to_owned()),
+    });
+
+    console.register("toggle", "toggle <fps|perf>", |world, args| {
+        let entities: Vec<Entity> = match args {
+            // The FPS text sits inside a background panel, which is what gets hidden
+            ["fps"] => {
+                let parents = world.read_storage::<UiParent>();
+
+                (&*world.entities(), &world.read_storage::<FpsDisplay>())
+                    .join()
+                    .map(|(entity, _)| parents.get(entity).map_or(entity, |parent| parent.0))
+                    .collect()
+            }
+            ["perf"] => (
+                &*world.entities(),
+                &world.read_storage::<PerformanceGraph>(),
+            )
+                .join()
+                .map(|(entity, _)| entity)
+                .collect(),
+            _ => return Err("Usage: toggle <fps|perf>".to_owned()),
+        };
+
+        Ok(if toggle_hidden(world, entities) {
+            "Hidden".to_owned()
+        } else {
+            "Shown".to_owned()
+        })
+    });
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum ConsolePart {
+    Background,
+    Log,
+    Input,
+}
+
+impl Component for ConsolePart {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[derive(Default)]
+pub struct UpdateConsole {
+    reader: Option<ReaderId<TimedInputEvent>>,
+}
+
+impl<'a> System<'a> for UpdateConsole {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, InputEvents>,
+        Write<'a, DebugConsole>,
+        ReadStorage<'a, ConsolePart>,
+        WriteStorage<'a, Text>,
+        WriteStorage<'a, Hidden>,
+    );
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(res.fetch_mut::<InputEvents>().register_reader());
+    }
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, input_events, mut console, console_parts, mut texts, mut hiddens) = data;
+
+        // Keys are hidden while the console is open, so it's toggled from the events instead
+        if let Some(reader) = &mut self.reader {
+            for timed_event in input_events.read(reader) {
+                match timed_event.event {
+                    InputEvent::KeyDown {
+                        keycode: KeyCode::Grave,
+                        repeat: false,
+                        ..
+                    } => console.open = !console.open,
+                    _ if !console.open => {}
+                    // The backtick that opened the console comes through as text too
+                    InputEvent::TextInput(character)
+                        if !character.is_control() && character != '`' =>
+                    {
+                        console.input.push(character)
+                    }
+                    InputEvent::KeyDown {
+                        keycode: KeyCode::Back,
+                        ..
+                    } => {
+                        console.input.pop();
+                    }
+                    InputEvent::KeyDown {
+                        keycode: KeyCode::Return,
+                        ..
+                    }
+                    | InputEvent::KeyDown {
+                        keycode: KeyCode::NumpadEnter,
+                        ..
+                    } => console.submit(),
+                    _ => {}
+                }
+            }
+        }
+
+        for (entity, console_part) in (&*entities, &console_parts).join() {
+            match console_part {
+                ConsolePart::Background => {
+                    if console.open {
+                        hiddens.remove(entity);
+                    } else {
+                        let _ = hiddens.insert(entity, Hidden);
+                    }
+                }
+                ConsolePart::Log => {
+                    if let Some(text) = texts.get_mut(entity) {
+                        let lines = console.lines();
+                        let first = lines.len().saturating_sub(VISIBLE_LINES);
+
+                        text.text = lines[first..].join("\n");
+                    }
+                }
+                ConsolePart::Input => {
+                    if let Some(text) = texts.get_mut(entity) {
+                        text.text = format!("> {}_", console.input);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.register::<ConsolePart>();
+
+    let mut console = DebugConsole::default();
+    register_builtin_commands(&mut console);
+    world.add_resource(console);
+
+    dispatcher_builder.add(UpdateConsole::default(), "update_console", &[]);
+
+    let background = world
+        .create_entity()
+        .with(Panel::Solid {
+            color: Color::new(0.0, 0.0, 0.0, 0.8),
+            border: None,
+        })
+        .with(UiContainer {
+            direction: Some(Direction::Vertical),
+            padding: Edges::uniform(PADDING),
+            clip: true,
+            ..Default::default()
+        })
+        .with(UiSize {
+            width: Size::Percent(1.0),
+            height: Size::Fixed((VISIBLE_LINES + 1) as f32 * LINE_HEIGHT + PADDING * 2.0),
+        })
+        .with(UiElement {
+            anchor: Some(Anchor::TopLeft),
+            origin: Some(Anchor::TopLeft),
+            ..Default::default()
+        })
+        .with(Layer(CONSOLE_LAYER))
+        .with(Hidden)
+        .with(ConsolePart::Background)
+        .build();
+
+    for console_part in &[ConsolePart::Log, ConsolePart::Input] {
+        world
+            .create_entity()
+            .with(Text {
+                scale: Scale::uniform(FONT_SIZE),
+                ..Default::default()
+            })
+            .with(UiElement::default())
+            .with(UiParent(background))
+            .with(Layer(CONSOLE_LAYER + 1))
+            .with(*console_part)
+            .build();
+    }
+}
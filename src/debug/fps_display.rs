@@ -1,14 +1,25 @@
 use crate::{
-    graphics::{ui::UiElement, Anchor, Layer},
-    renderers::text::Text,
+    graphics::{
+        ui::{
+            layout::{Direction, Edges, UiContainer, UiParent},
+            UiElement,
+        },
+        Anchor, Layer,
+    },
+    renderers::{panel::Panel, text::Text},
+};
+use ggez::{
+    graphics::{Color, Scale},
+    Context,
 };
-use ggez::{graphics::Scale, Context};
 use smart_default::SmartDefault;
 use specs::prelude::*;
 use std::time::Instant;
 
 static UPDATE_INTERVAL: f64 = 0.5;
 static FONT_SIZE: f32 = 48.0;
+static MARGIN: f32 = 5.0;
+static PADDING: f32 = 5.0;
 
 #[derive(Default)]
 pub struct FpsDisplay;
@@ -54,18 +65,38 @@ pub fn setup<'a, 'b>(
 
     dispatcher_builder.add(UpdateFpsDisplay::default(), "update_fps_display", &[]);
 
+    // The background keeps the text readable on light scenes
+    let background = world
+        .create_entity()
+        .with(Panel::Solid {
+            color: Color::new(0.0, 0.0, 0.0, 0.6),
+            border: None,
+        })
+        .with(UiContainer {
+            direction: Some(Direction::Vertical),
+            padding: Edges::uniform(PADDING),
+            ..Default::default()
+        })
+        .with(Layer(10))
+        .with(UiElement {
+            anchor: Some(Anchor::TopLeft),
+            origin: Some(Anchor::TopLeft),
+            margin: Edges::uniform(MARGIN),
+            ..Default::default()
+        })
+        .build();
+
     world
         .create_entity()
         .with(Text {
             text: String::default(),
             font: "roboto",
             scale: Scale::uniform(FONT_SIZE),
+            ..Default::default()
         })
         .with(FpsDisplay::default())
-        .with(Layer(10))
-        .with(UiElement {
-            anchor: Some(Anchor::TopLeft),
-            origin: Some(Anchor::TopLeft),
-        })
+        .with(Layer(11))
+        .with(UiElement::default())
+        .with(UiParent(background))
         .build();
 }
@@ -1,7 +1,9 @@
+pub mod console;
 pub mod fps_display;
 pub mod performance_graph;
 
-use ggez::Context;
+use crate::plugin::Plugin;
+use ggez::{Context, GameResult};
 use specs::prelude::*;
 
 pub fn setup<'a, 'b>(
@@ -11,4 +13,22 @@ pub fn setup<'a, 'b>(
 ) {
     fps_display::setup(ctx, world, dispatcher_builder);
     performance_graph::setup(ctx, world, dispatcher_builder);
+    console::setup(ctx, world, dispatcher_builder);
+}
+
+/// The FPS counter, frame time graph and console overlay, it uses the renderers
+/// from `RenderersPlugin` so that needs to be added too
+pub struct DebugPlugin;
+
+impl<'a, 'b> Plugin<'a, 'b> for DebugPlugin {
+    fn build(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+    ) -> GameResult {
+        setup(ctx, world, dispatcher_builder);
+
+        Ok(())
+    }
 }
@@ -1,5 +1,8 @@
 use crate::{
-    graphics::{ui::UiElement, Anchor, Layer},
+    graphics::{
+        ui::{layout::Edges, UiElement},
+        Anchor, Layer,
+    },
     renderers::column_graph::ColumnGraph,
     DeltaTime,
 };
@@ -8,6 +11,7 @@ use specs::prelude::*;
 
 static COLUMNS: usize = 50;
 static COLUMN_WIDTH: f32 = 5.0;
+static MARGIN: f32 = 5.0;
 
 #[derive(Default)]
 pub struct PerformanceGraph;
@@ -61,6 +65,8 @@ pub fn setup<'a, 'b>(
         .with(UiElement {
             anchor: Some(Anchor::BottomLeft),
             origin: Some(Anchor::BottomLeft),
+            margin: Edges::uniform(MARGIN),
+            ..Default::default()
         })
         .build();
 }
@@ -1,11 +1,13 @@
 pub mod camera;
 pub mod rendering;
+pub mod settings;
+pub mod text;
 pub mod ui;
 
 use ggez::{
-    graphics::{Font, Rect},
+    graphics::{Font, Image, Rect},
     nalgebra::{Point2, Vector2},
-    Context,
+    Context, GameResult,
 };
 use smart_default::SmartDefault;
 use specs::prelude::*;
@@ -65,18 +67,22 @@ impl Component for Layer {
     type Storage = VecStorage<Self>;
 }
 
-// TODO: Make it so ScreenSize isn't 0 0 by default
+/// The size of the area everything is laid out in, see `settings::Viewport`
 #[derive(SmartDefault)]
 pub struct ScreenSize(#[default(Vector2::new(0.0, 0.0))] pub Vector2<f32>);
 
 #[derive(Default)]
 pub struct Fonts(pub HashMap<&'static str, Font>);
 
+/// Images loaded with `ContextCommand::LoadImage`, by name
+#[derive(Default)]
+pub struct Images(pub HashMap<String, Image>);
+
 pub fn setup<'a, 'b>(
     ctx: &mut Context,
     world: &mut World,
     dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
-) {
+) -> GameResult {
     world.register::<Position>();
     world.register::<Layer>();
 
@@ -86,13 +92,18 @@ pub fn setup<'a, 'b>(
 
         fonts.insert(
             "roboto",
-            Font::new_glyph_font_bytes(ctx, include_bytes!("../../resources/Roboto.ttf")).unwrap(),
+            Font::new_glyph_font_bytes(ctx, include_bytes!("../../resources/Roboto.ttf"))?,
         );
 
         fonts
     }));
+    world.add_resource(Images::default());
 
     camera::setup(ctx, world, dispatcher_builder);
     ui::setup(ctx, world, dispatcher_builder);
     rendering::setup(ctx, world, dispatcher_builder);
+    settings::setup(ctx, world, dispatcher_builder);
+    text::setup(ctx, world, dispatcher_builder);
+
+    Ok(())
 }
@@ -1,29 +1,44 @@
 use super::{
     camera::{world_to_screen, ActiveCamera, Camera},
-    ui::UiElement,
-    Fonts, Layer, Position, ScreenSize,
+    text::{layout_text, Overflow},
+    ui::{
+        layout::{intersect, ClipRect, UiRect},
+        UiElement,
+    },
+    Fonts, Images, Layer, Position, ScreenSize,
 };
+use crate::timestep::{interpolate, InterpolationAlpha, PreviousPosition};
 use ggez::{
     graphics::{
-        self, spritebatch, Color, DrawParam, Drawable, Font, Image, MeshBuilder, Rect, Scale,
+        self, spritebatch, Canvas, Color, DrawParam, Drawable, Font, Image, MeshBuilder, Rect,
+        Scale,
     },
     nalgebra::{Point2, Vector2},
-    Context, GameResult,
+    Context, GameError, GameResult,
 };
+use log::error;
 use specs::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Clone)]
 pub enum ImageBuilder {
-    Solid { size: u16, color: Color },
+    Solid {
+        size: u16,
+        color: Color,
+    },
+    /// An image loaded into `Images` with `ContextCommand::LoadImage`
+    Loaded(String),
 }
 
 impl ImageBuilder {
-    pub fn build(self, ctx: &mut Context) -> GameResult<Image> {
+    pub fn build(self, ctx: &mut Context, images: &HashMap<String, Image>) -> GameResult<Image> {
         use ImageBuilder::*;
 
         match self {
             Solid { size, color } => Image::solid(ctx, size, color),
+            Loaded(name) => images.get(&name).cloned().ok_or_else(|| {
+                GameError::ResourceLoadError(format!("No image has been loaded as {:?}", name))
+            }),
         }
     }
 }
@@ -43,6 +58,8 @@ pub enum RenderInstruction {
         text: String,
         font: &'static str,
         scale: Scale,
+        max_width: Option<f32>,
+        overflow: Overflow,
     },
 }
 
@@ -51,16 +68,18 @@ impl RenderInstruction {
         self,
         ctx: &mut Context,
         fonts: &HashMap<&'static str, Font>,
+        images: &HashMap<String, Image>,
     ) -> GameResult<Box<Drawable>> {
         use RenderInstruction::*;
 
         Ok(match self {
-            Image(image_builder) => Box::new(image_builder.build(ctx)?),
+            Image(image_builder) => Box::new(image_builder.build(ctx, images)?),
             SpriteBatch {
                 image_builder,
                 sprites,
             } => {
-                let mut spritebatch = spritebatch::SpriteBatch::new(image_builder.build(ctx)?);
+                let mut spritebatch =
+                    spritebatch::SpriteBatch::new(image_builder.build(ctx, images)?);
 
                 for sprite in sprites {
                     spritebatch.add(sprite);
@@ -69,13 +88,15 @@ impl RenderInstruction {
                 Box::new(spritebatch)
             }
             Mesh(mesh_builder) => Box::new(mesh_builder.build(ctx)?),
-            Text { text, font, scale } => {
-                let mut drawable_text = graphics::Text::new(text);
-
-                drawable_text.set_font(fonts.get(font).cloned().unwrap_or_default(), scale);
-
-                Box::new(drawable_text)
-            }
+            Text {
+                text,
+                font,
+                scale,
+                max_width,
+                overflow,
+            } => Box::new(layout_text(
+                ctx, fonts, &text, font, scale, max_width, overflow,
+            )),
         })
     }
 }
@@ -89,27 +110,142 @@ impl Component for Renderable {
     type Storage = VecStorage<Self>;
 }
 
+/// The area an entity covered the last time it was rendered,
+/// in screen space for UI elements and in world space for everything else
+pub struct Bounds(pub Rect);
+
+impl Component for Bounds {
+    type Storage = VecStorage<Self>;
+}
+
+/// Everything that failed to render this frame, those entities were skipped
+#[derive(Default)]
+pub struct RenderErrors {
+    pub errors: Vec<(Entity, String)>,
+    logged: HashSet<Entity>,
+}
+
+impl RenderErrors {
+    fn report(&mut self, entity: Entity, err: &GameError) {
+        // Only log the first time so a broken entity doesn't flood the log every frame
+        if self.logged.insert(entity) {
+            error!("Failed to render entity {}: {}", entity.id(), err);
+        }
+
+        self.errors.push((entity, err.to_string()));
+    }
+}
+
 pub struct RenderingSystem<'c> {
     ctx: &'c mut Context,
+    clip_canvas: &'c mut Option<Canvas>,
+    /// The clip rect of the entities being drawn to `clip_canvas` right now
+    clip: Option<Rect>,
+    /// What went wrong that wasn't down to any one entity
+    error: Option<GameError>,
 }
 
 impl<'c> RenderingSystem<'c> {
-    pub fn new(ctx: &'c mut Context) -> RenderingSystem<'c> {
-        RenderingSystem { ctx }
+    /// `clip_canvas` is kept between frames for drawing clipped UI,
+    /// it's created when first needed and should be reset when the window is resized
+    pub fn new(ctx: &'c mut Context, clip_canvas: &'c mut Option<Canvas>) -> RenderingSystem<'c> {
+        RenderingSystem {
+            ctx,
+            clip_canvas,
+            clip: None,
+            error: None,
+        }
+    }
+
+    /// Whether rendering failed as a whole, entities that failed on their own
+    /// are in `RenderErrors` instead
+    pub fn finish(self) -> GameResult {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// ggez has no scissor test, so clipped drawables are drawn to a canvas
+    /// and only the part inside `clip` is copied over to the screen.
+    /// Entities drawn one after another with the same clip share a single copy
+    fn begin_clip(&mut self, clip: Rect) -> GameResult {
+        if self.clip == Some(clip) {
+            return Ok(());
+        }
+
+        self.end_clip()?;
+
+        if self.clip_canvas.is_none() {
+            *self.clip_canvas = Some(Canvas::with_window_size(self.ctx)?);
+        }
+
+        if let Some(canvas) = &*self.clip_canvas {
+            graphics::set_canvas(self.ctx, Some(canvas));
+            graphics::clear(self.ctx, Color::new(0.0, 0.0, 0.0, 0.0));
+            self.clip = Some(clip);
+        }
+
+        Ok(())
+    }
+
+    /// Copies what was drawn since `begin_clip` over to the screen
+    fn end_clip(&mut self) -> GameResult {
+        let clip = match self.clip.take() {
+            Some(clip) => clip,
+            None => return Ok(()),
+        };
+
+        graphics::set_canvas(self.ctx, None);
+
+        if let Some(canvas) = &*self.clip_canvas {
+            let screen = graphics::screen_coordinates(self.ctx);
+            let image = canvas.image();
+            let image_size = Vector2::new(f32::from(image.width()), f32::from(image.height()));
+
+            // Drawn as an image since drawing the canvas flips the whole thing, not just the clip
+            image.draw(self.ctx, clip_copy(clip, screen, image_size))?;
+        }
+
+        Ok(())
     }
 }
 
+/// How to copy the part of a canvas covering `screen` inside `clip` over to the same place
+/// on the screen. Canvases are stored upside down, so the source is flipped vertically
+fn clip_copy(clip: Rect, screen: Rect, image_size: Vector2<f32>) -> DrawParam {
+    DrawParam::default()
+        .src(Rect::new(
+            (clip.x - screen.x) / screen.w,
+            1.0 - (clip.y + clip.h - screen.y) / screen.h,
+            clip.w / screen.w,
+            clip.h / screen.h,
+        ))
+        .dest(Point2::new(clip.x, clip.y + clip.h))
+        .scale(Vector2::new(
+            screen.w / image_size.x,
+            -screen.h / image_size.y,
+        ))
+}
+
 impl<'a, 'c> System<'a> for RenderingSystem<'c> {
     #[allow(clippy::type_complexity)]
     type SystemData = (
         Entities<'a>,
         Read<'a, ScreenSize>,
         Read<'a, Fonts>,
+        Read<'a, Images>,
+        Read<'a, InterpolationAlpha>,
         Write<'a, ActiveCamera>,
+        Write<'a, RenderErrors>,
         WriteStorage<'a, Renderable>,
+        WriteStorage<'a, Bounds>,
         ReadStorage<'a, UiElement>,
+        ReadStorage<'a, UiRect>,
+        ReadStorage<'a, ClipRect>,
         ReadStorage<'a, Layer>,
         ReadStorage<'a, Position>,
+        ReadStorage<'a, PreviousPosition>,
         ReadStorage<'a, Camera>,
     );
 
@@ -118,20 +254,38 @@ impl<'a, 'c> System<'a> for RenderingSystem<'c> {
             entities,
             screen_size,
             fonts,
+            images,
+            interpolation_alpha,
             active_camera,
+            mut render_errors,
             mut renderables,
+            mut bounds,
             ui_elements,
+            ui_rects,
+            clip_rects,
             layers,
             positions,
+            previous_positions,
             cameras,
         ) = data;
 
         let screen_size = screen_size.0;
+        let interpolation_alpha = interpolation_alpha.0;
+
+        render_errors.errors.clear();
+        // Forget deleted entities so the set doesn't grow forever
+        render_errors
+            .logged
+            .retain(|entity| entities.is_alive(*entity));
 
         if let Some(active_camera) = active_camera.0 {
             if let Some(camera) = cameras.get(active_camera) {
                 if let Some(camera_position) = positions.get(active_camera) {
-                    let camera_position = camera_position.0;
+                    let camera_position = interpolate(
+                        camera_position,
+                        previous_positions.get(active_camera),
+                        interpolation_alpha,
+                    );
 
                     let mut renderable_entities: Vec<(Entity, Renderable)> =
                         (&*entities, renderables.drain()).join().collect();
@@ -141,33 +295,62 @@ impl<'a, 'c> System<'a> for RenderingSystem<'c> {
                     });
 
                     for (entity, renderable) in renderable_entities {
+                        // UI that wasn't laid out is hidden
+                        if ui_elements.contains(entity) && !ui_rects.contains(entity) {
+                            bounds.remove(entity);
+                            continue;
+                        }
+
                         let mut draw_param =
                             renderable.draw_param.unwrap_or_else(DrawParam::default);
 
-                        let drawable = renderable
+                        let drawable = match renderable
                             .instruction
-                            .construct(self.ctx, &fonts.0)
-                            .unwrap();
-
-                        if let Some(ui_element) = &ui_elements.get(entity) {
-                            draw_param.dest = ui_element
-                                .anchor
-                                .unwrap_or_default()
-                                .get_postion(Rect::new(0.0, 0.0, screen_size.x, screen_size.y))
-                                .into();
-
-                            if let Some(dimensions) = drawable.dimensions(self.ctx) {
-                                draw_param.dest = (Point2::from(draw_param.dest)
-                                    - ui_element
-                                        .origin
-                                        .unwrap_or_default()
-                                        .get_postion(dimensions)
-                                        .coords)
-                                    .into();
+                            .construct(self.ctx, &fonts.0, &images.0)
+                        {
+                            Ok(drawable) => drawable,
+                            Err(err) => {
+                                render_errors.report(entity, &err);
+                                bounds.remove(entity);
+                                continue;
+                            }
+                        };
+
+                        let dimensions = drawable.dimensions(self.ctx);
+
+                        let area = if ui_elements.contains(entity) {
+                            // The dest the user gave is kept as an offset from the layout
+                            if let Some(ui_rect) = ui_rects.get(entity) {
+                                draw_param.dest.x += ui_rect.0.x;
+                                draw_param.dest.y += ui_rect.0.y;
+
+                                if let Some(dimensions) = dimensions {
+                                    draw_param.dest.x -= dimensions.x;
+                                    draw_param.dest.y -= dimensions.y;
+                                }
                             }
+
+                            dimensions.map(|dimensions| {
+                                covered_area(
+                                    draw_param.dest.into(),
+                                    draw_param.scale.into(),
+                                    dimensions,
+                                )
+                            })
                         } else {
+                            let world_dest = positions
+                                .get(entity)
+                                .map(|position| {
+                                    interpolate(
+                                        position,
+                                        previous_positions.get(entity),
+                                        interpolation_alpha,
+                                    )
+                                })
+                                .unwrap_or_else(|| draw_param.dest.into());
+
                             draw_param.dest = world_to_screen(
-                                draw_param.dest.into(),
+                                world_dest,
                                 camera_position,
                                 camera.zoom,
                                 screen_size,
@@ -175,13 +358,39 @@ impl<'a, 'c> System<'a> for RenderingSystem<'c> {
                             .into();
 
                             draw_param.scale = Vector2::repeat(camera.zoom).into();
+
+                            dimensions.map(|dimensions| {
+                                covered_area(world_dest, Vector2::repeat(1.0), dimensions)
+                            })
+                        };
+
+                        let clip = clip_rects.get(entity).map(|clip_rect| clip_rect.0);
+
+                        if let Some(area) = area {
+                            let area = clip.map_or(area, |clip| intersect(area, clip));
+                            let _ = bounds.insert(entity, Bounds(area));
+                        } else {
+                            bounds.remove(entity);
                         }
 
-                        if let Some(position) = positions.get(entity) {
-                            draw_param.dest = position.0.into();
+                        let drawn = match clip {
+                            // None of it would be visible
+                            Some(clip) if clip.w <= 0.0 || clip.h <= 0.0 => Ok(()),
+                            Some(clip) => self
+                                .begin_clip(clip)
+                                .and_then(|_| drawable.draw(self.ctx, draw_param)),
+                            None => self
+                                .end_clip()
+                                .and_then(|_| drawable.draw(self.ctx, draw_param)),
+                        };
+
+                        if let Err(err) = drawn {
+                            render_errors.report(entity, &err);
                         }
+                    }
 
-                        drawable.draw(self.ctx, draw_param).unwrap();
+                    if let Err(err) = self.end_clip() {
+                        self.error = Some(err);
                     }
                 }
             }
@@ -189,10 +398,76 @@ impl<'a, 'c> System<'a> for RenderingSystem<'c> {
     }
 }
 
+/// The area `dimensions` ends up covering when drawn at `dest` with `scale`
+fn covered_area(dest: Point2<f32>, scale: Vector2<f32>, dimensions: Rect) -> Rect {
+    let corner = Point2::new(
+        dest.x + dimensions.x * scale.x,
+        dest.y + dimensions.y * scale.y,
+    );
+    let size = Vector2::new(dimensions.w * scale.x, dimensions.h * scale.y);
+
+    Rect::new(
+        corner.x.min(corner.x + size.x),
+        corner.y.min(corner.y + size.y),
+        size.x.abs(),
+        size.y.abs(),
+    )
+}
+
 pub fn setup<'a, 'b>(
     _ctx: &mut Context,
     world: &mut World,
     _dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
 ) {
     world.register::<Renderable>();
+    world.register::<Bounds>();
+
+    world.add_resource(RenderErrors::default());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    fn assert_clip_copy(param: DrawParam, src: Rect, dest: Point2<f32>, scale: Vector2<f32>) {
+        assert_close(param.src.x, src.x);
+        assert_close(param.src.y, src.y);
+        assert_close(param.src.w, src.w);
+        assert_close(param.src.h, src.h);
+        assert_close(param.dest.x, dest.x);
+        assert_close(param.dest.y, dest.y);
+        assert_close(param.scale.x, scale.x);
+        assert_close(param.scale.y, scale.y);
+    }
+
+    #[test]
+    fn clip_copy_flips_the_source_rows() {
+        let screen = Rect::new(0.0, 0.0, 200.0, 100.0);
+        let clip = Rect::new(20.0, 10.0, 50.0, 30.0);
+
+        assert_clip_copy(
+            clip_copy(clip, screen, Vector2::new(200.0, 100.0)),
+            Rect::new(0.1, 0.6, 0.25, 0.3),
+            Point2::new(20.0, 40.0),
+            Vector2::new(1.0, -1.0),
+        );
+    }
+
+    #[test]
+    fn clip_copy_with_scaled_and_offset_screen_coordinates() {
+        // A window twice the size of the screen coordinates, which start at 100, 50
+        let screen = Rect::new(100.0, 50.0, 200.0, 100.0);
+        let clip = Rect::new(100.0, 100.0, 100.0, 50.0);
+
+        assert_clip_copy(
+            clip_copy(clip, screen, Vector2::new(400.0, 200.0)),
+            Rect::new(0.0, 0.0, 0.5, 0.5),
+            Point2::new(100.0, 150.0),
+            Vector2::new(0.5, -0.5),
+        );
+    }
 }
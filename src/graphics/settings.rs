@@ -0,0 +1,198 @@
+use ggez::{
+    graphics::{self, Color, DrawMode, DrawParam, FilterMode, MeshBuilder, Rect, BLACK},
+    nalgebra::{Point2, Vector2},
+    Context, GameResult,
+};
+use smart_default::SmartDefault;
+use specs::prelude::*;
+
+/// How the virtual resolution is fit into the window
+#[derive(Clone, Copy, Debug, PartialEq, SmartDefault)]
+pub enum ScaleMode {
+    /// Stretches over the whole window, ignoring the aspect ratio
+    Stretch,
+    /// Scales up as far as it fits, with bars around whatever is left over
+    #[default]
+    Fit,
+    /// Like `Fit` but only scales by whole numbers so every pixel stays the same size
+    PixelPerfect,
+    /// Scales like `Fit` but grows the screen to fill the window instead of adding bars
+    Expand,
+}
+
+/// How `Planet` draws every frame, systems can change this whenever
+#[derive(Clone, SmartDefault)]
+pub struct RenderSettings {
+    /// What the screen is cleared to before drawing, `None` leaves last frame there
+    #[default(Some(Color::new(0.1, 0.2, 0.3, 1.0)))]
+    pub clear_color: Option<Color>,
+    /// The most frames drawn a second, `None` for no limit.
+    /// Vsync can only be picked when the window is made, with ggez's `WindowSetup`
+    pub frame_cap: Option<u32>,
+    /// How images are scaled, `FilterMode::Nearest` keeps pixel art sharp
+    #[default(FilterMode::Linear)]
+    pub filter_mode: FilterMode,
+    /// Lays the screen out at this size no matter how big the window is,
+    /// `None` uses the window size
+    pub virtual_resolution: Option<Vector2<f32>>,
+    pub scale_mode: ScaleMode,
+    #[default(BLACK)]
+    pub letterbox_color: Color,
+}
+
+/// Where the screen ends up in the window, `Planet` works this out
+/// from `RenderSettings` every frame. `ScreenSize`, `MousePosition` and
+/// UI anchoring are all in the same units as `screen_coordinates`
+#[derive(Clone, Copy, SmartDefault)]
+pub struct Viewport {
+    #[default(Vector2::zeros())]
+    pub window_size: Vector2<f32>,
+    /// The part of the game's coordinates that fills the window
+    #[default(Rect::zero())]
+    pub screen_coordinates: Rect,
+    /// The area everything is laid out in, anything outside it is letterboxed
+    #[default(Rect::zero())]
+    pub screen: Rect,
+}
+
+impl Viewport {
+    pub fn new(window_size: Vector2<f32>, render_settings: &RenderSettings) -> Self {
+        let whole_window = Rect::new(0.0, 0.0, window_size.x, window_size.y);
+
+        let virtual_resolution = match render_settings.virtual_resolution {
+            Some(virtual_resolution) if window_size.x > 0.0 && window_size.y > 0.0 => {
+                virtual_resolution
+            }
+            _ => {
+                return Self {
+                    window_size,
+                    screen_coordinates: whole_window,
+                    screen: whole_window,
+                }
+            }
+        };
+
+        let fit_scale =
+            (window_size.x / virtual_resolution.x).min(window_size.y / virtual_resolution.y);
+
+        let (screen_coordinates, screen) = match render_settings.scale_mode {
+            ScaleMode::Stretch => {
+                let screen = Rect::new(0.0, 0.0, virtual_resolution.x, virtual_resolution.y);
+
+                (screen, screen)
+            }
+            ScaleMode::Fit | ScaleMode::PixelPerfect => {
+                let scale = if render_settings.scale_mode == ScaleMode::PixelPerfect {
+                    fit_scale.floor().max(1.0)
+                } else {
+                    fit_scale
+                };
+                let visible_size = window_size / scale;
+
+                (
+                    Rect::new(
+                        (virtual_resolution.x - visible_size.x) / 2.0,
+                        (virtual_resolution.y - visible_size.y) / 2.0,
+                        visible_size.x,
+                        visible_size.y,
+                    ),
+                    Rect::new(0.0, 0.0, virtual_resolution.x, virtual_resolution.y),
+                )
+            }
+            ScaleMode::Expand => {
+                let visible_size = window_size / fit_scale;
+                let screen = Rect::new(0.0, 0.0, visible_size.x, visible_size.y);
+
+                (screen, screen)
+            }
+        };
+
+        Self {
+            window_size,
+            screen_coordinates,
+            screen,
+        }
+    }
+
+    pub fn screen_size(&self) -> Vector2<f32> {
+        Vector2::new(self.screen.w, self.screen.h)
+    }
+
+    /// How many units of the game's coordinates one window pixel takes up
+    pub fn scale(&self) -> Vector2<f32> {
+        if self.window_size.x <= 0.0 || self.window_size.y <= 0.0 {
+            return Vector2::repeat(1.0);
+        }
+
+        Vector2::new(
+            self.screen_coordinates.w / self.window_size.x,
+            self.screen_coordinates.h / self.window_size.y,
+        )
+    }
+
+    pub fn window_to_screen(&self, point: Point2<f32>) -> Point2<f32> {
+        let scale = self.scale();
+
+        Point2::new(
+            self.screen_coordinates.x + point.x * scale.x,
+            self.screen_coordinates.y + point.y * scale.y,
+        )
+    }
+
+    pub fn window_to_screen_delta(&self, delta: Vector2<f32>) -> Vector2<f32> {
+        delta.component_mul(&self.scale())
+    }
+}
+
+/// Covers whatever is outside of `viewport.screen`
+pub fn draw_letterbox(
+    ctx: &mut Context,
+    render_settings: &RenderSettings,
+    viewport: &Viewport,
+) -> GameResult {
+    let visible = viewport.screen_coordinates;
+    let screen = viewport.screen;
+
+    let bars = [
+        // Left and right
+        Rect::new(visible.x, visible.y, screen.x - visible.x, visible.h),
+        Rect::new(
+            screen.right(),
+            visible.y,
+            visible.right() - screen.right(),
+            visible.h,
+        ),
+        // Top and bottom
+        Rect::new(visible.x, visible.y, visible.w, screen.y - visible.y),
+        Rect::new(
+            visible.x,
+            screen.bottom(),
+            visible.w,
+            visible.bottom() - screen.bottom(),
+        ),
+    ];
+
+    let mut mesh_builder = MeshBuilder::new();
+    let mut has_bars = false;
+
+    for bar in bars.iter().filter(|bar| bar.w > 0.0 && bar.h > 0.0) {
+        mesh_builder.rectangle(DrawMode::fill(), *bar, render_settings.letterbox_color);
+        has_bars = true;
+    }
+
+    if has_bars {
+        let mesh = mesh_builder.build(ctx)?;
+        graphics::draw(ctx, &mesh, DrawParam::default())?;
+    }
+
+    Ok(())
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    _dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.add_resource(RenderSettings::default());
+    world.add_resource(Viewport::default());
+}
@@ -0,0 +1,197 @@
+use ggez::{
+    graphics::{self, Align, Font, Scale},
+    nalgebra::{Point2, Vector2},
+    Context,
+};
+use smart_default::SmartDefault;
+use specs::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+    sync::{Mutex, PoisonError},
+};
+
+/// The most sizes kept around before the cache starts over,
+/// so text that changes every frame doesn't grow it forever
+static CACHE_SIZE: usize = 1024;
+
+/// What happens to text wider than its max width
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, SmartDefault)]
+pub enum Overflow {
+    /// Breaks it into lines between words
+    #[default]
+    Wrap,
+    /// Keeps it to one line, cutting it off with an ellipsis
+    Ellipsis,
+}
+
+/// Lays out text the way it'll be drawn
+pub fn layout_text(
+    ctx: &mut Context,
+    fonts: &HashMap<&'static str, Font>,
+    text: &str,
+    font: &'static str,
+    scale: Scale,
+    max_width: Option<f32>,
+    overflow: Overflow,
+) -> graphics::Text {
+    let font = fonts.get(font).cloned().unwrap_or_default();
+    let new_text = |text: String| {
+        let mut drawable_text = graphics::Text::new(text);
+        drawable_text.set_font(font, scale);
+        drawable_text
+    };
+
+    let mut drawable_text = new_text(text.to_owned());
+
+    if let Some(max_width) = max_width {
+        match overflow {
+            Overflow::Wrap => {
+                drawable_text.set_bounds(Point2::new(max_width, std::f32::INFINITY), Align::Left);
+            }
+            Overflow::Ellipsis => {
+                if drawable_text.width(ctx) as f32 > max_width {
+                    let chars: Vec<char> = text.chars().collect();
+                    let truncated = |len: usize| {
+                        new_text(
+                            chars[..len]
+                                .iter()
+                                .collect::<String>()
+                                .trim_end()
+                                .to_owned()
+                                + "...",
+                        )
+                    };
+
+                    // Find the most characters that still fit with the ellipsis on the end
+                    let (mut fits, mut too_long) = (0, chars.len());
+
+                    while too_long - fits > 1 {
+                        let middle = (fits + too_long) / 2;
+
+                        if truncated(middle).width(ctx) as f32 <= max_width {
+                            fits = middle;
+                        } else {
+                            too_long = middle;
+                        }
+                    }
+
+                    drawable_text = truncated(fits);
+                }
+            }
+        }
+    }
+
+    drawable_text
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct TextKey {
+    text: String,
+    font: &'static str,
+    scale: (u32, u32),
+    max_width: Option<u32>,
+    overflow: Overflow,
+}
+
+/// Measures text for systems that need its size before it's drawn.
+/// Text that hasn't been measured yet is queued and `Planet` measures it
+/// after the systems have run, so its size is there the frame after.
+/// Queueing doesn't need `Write`, so systems reading sizes can run in parallel
+#[derive(Default)]
+pub struct TextMetrics {
+    sizes: HashMap<TextKey, Vector2<f32>>,
+    pending: Mutex<HashSet<TextKey>>,
+}
+
+impl TextMetrics {
+    pub fn size(
+        &self,
+        text: &str,
+        font: &'static str,
+        scale: Scale,
+        max_width: Option<f32>,
+        overflow: Overflow,
+    ) -> Option<Vector2<f32>> {
+        let key = TextKey {
+            text: text.to_owned(),
+            font,
+            scale: (scale.x.to_bits(), scale.y.to_bits()),
+            max_width: max_width.map(f32::to_bits),
+            overflow,
+        };
+
+        let size = self.sizes.get(&key).cloned();
+
+        if size.is_none() {
+            self.pending
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(key);
+        }
+
+        size
+    }
+
+    pub fn measure_pending(&mut self, ctx: &mut Context, fonts: &HashMap<&'static str, Font>) {
+        let pending = mem::replace(
+            self.pending
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner),
+            HashSet::new(),
+        );
+
+        if self.sizes.len() + pending.len() > CACHE_SIZE {
+            self.sizes.clear();
+        }
+
+        for key in pending {
+            let (width, height) = layout_text(
+                ctx,
+                fonts,
+                &key.text,
+                key.font,
+                Scale {
+                    x: f32::from_bits(key.scale.0),
+                    y: f32::from_bits(key.scale.1),
+                },
+                key.max_width.map(f32::from_bits),
+                key.overflow,
+            )
+            .dimensions(ctx);
+
+            self.sizes
+                .insert(key, Vector2::new(width as f32, height as f32));
+        }
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    _dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.add_resource(TextMetrics::default());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unmeasured_text_is_queued_once() {
+        let text_metrics = TextMetrics::default();
+        let scale = Scale::uniform(16.0);
+
+        assert_eq!(
+            text_metrics.size("hello", "default", scale, None, Overflow::Wrap),
+            None
+        );
+        assert_eq!(
+            text_metrics.size("hello", "default", scale, None, Overflow::Wrap),
+            None
+        );
+
+        assert_eq!(text_metrics.pending.lock().unwrap().len(), 1);
+    }
+}
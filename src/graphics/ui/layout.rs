@@ -0,0 +1,604 @@
+use super::{Hidden, UiElement};
+use crate::graphics::{rendering::Bounds, Position, ScreenSize};
+use ggez::{
+    graphics::Rect,
+    nalgebra::{Point2, Vector2},
+    Context,
+};
+use smart_default::SmartDefault;
+use specs::prelude::*;
+use std::collections::HashMap;
+
+/// Spacing on each side of a rect, used for margins and padding
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub struct Edges {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl Edges {
+    pub fn uniform(size: f32) -> Self {
+        Self {
+            left: size,
+            right: size,
+            top: size,
+            bottom: size,
+        }
+    }
+
+    pub fn horizontal(&self) -> f32 {
+        self.left + self.right
+    }
+
+    pub fn vertical(&self) -> f32 {
+        self.top + self.bottom
+    }
+
+    pub fn shrink(&self, rect: Rect) -> Rect {
+        Rect::new(
+            rect.x + self.left,
+            rect.y + self.top,
+            (rect.w - self.horizontal()).max(0.0),
+            (rect.h - self.vertical()).max(0.0),
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, SmartDefault)]
+pub enum Size {
+    /// Fits the children of a stacking container, otherwise whatever size
+    /// the element was rendered at last frame
+    #[default]
+    Auto,
+    Fixed(f32),
+    /// A fraction of the parent's content area, 1.0 being all of it
+    Percent(f32),
+}
+
+impl Size {
+    fn resolve(self, available: f32, auto: f32) -> f32 {
+        use Size::*;
+
+        match self {
+            Auto => auto,
+            Fixed(size) => size,
+            Percent(percent) => available * percent,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+pub struct UiSize {
+    pub width: Size,
+    pub height: Size,
+}
+
+impl Component for UiSize {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Lays the element out inside another element's content area instead of the screen
+pub struct UiParent(pub Entity);
+
+impl Component for UiParent {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(SmartDefault)]
+pub struct UiContainer {
+    /// Stacks the children by their `UiElement::order`,
+    /// otherwise each child is anchored on its own
+    pub direction: Option<Direction>,
+    pub padding: Edges,
+    /// Space between stacked children
+    pub spacing: f32,
+    /// Cuts off anything of the children outside of the content area
+    pub clip: bool,
+    /// Moves the children up and left by this much
+    #[default(Vector2::zeros())]
+    pub scroll: Vector2<f32>,
+}
+
+impl Component for UiContainer {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Where the element was laid out this frame, in screen space
+pub struct UiRect(pub Rect);
+
+impl Component for UiRect {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// How much room the children of a container take up this frame,
+/// measured from the top left of its content area as if it wasn't scrolled
+pub struct ContentSize(pub Vector2<f32>);
+
+impl Component for ContentSize {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// The area the element is cut off to this frame, in screen space.
+/// Given to everything inside a clipping `UiContainer`
+pub struct ClipRect(pub Rect);
+
+impl Component for ClipRect {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// The area both rects cover, empty if they don't overlap
+pub fn intersect(a: Rect, b: Rect) -> Rect {
+    let x = a.x.max(b.x);
+    let y = a.y.max(b.y);
+
+    Rect::new(
+        x,
+        y,
+        ((a.x + a.w).min(b.x + b.w) - x).max(0.0),
+        ((a.y + a.h).min(b.y + b.h) - y).max(0.0),
+    )
+}
+
+#[derive(Default)]
+struct Placed {
+    rects: Vec<(Entity, Rect, Option<Rect>)>,
+    content_sizes: Vec<(Entity, Vector2<f32>)>,
+}
+
+struct Layout<'s, 'a: 's> {
+    ui_elements: &'s ReadStorage<'a, UiElement>,
+    sizes: &'s ReadStorage<'a, UiSize>,
+    containers: &'s ReadStorage<'a, UiContainer>,
+    bounds: &'s ReadStorage<'a, Bounds>,
+    positions: &'s ReadStorage<'a, Position>,
+    children: HashMap<Entity, Vec<Entity>>,
+}
+
+impl<'s, 'a> Layout<'s, 'a> {
+    fn margin(&self, entity: Entity) -> Edges {
+        self.ui_elements
+            .get(entity)
+            .map(|ui_element| ui_element.margin)
+            .unwrap_or_default()
+    }
+
+    /// The size of the element without its margin
+    fn measure(&self, entity: Entity, available: Vector2<f32>) -> Vector2<f32> {
+        let size = self.sizes.get(entity).cloned().unwrap_or_default();
+        let auto = self
+            .measure_children(entity, available)
+            .or_else(|| {
+                self.bounds
+                    .get(entity)
+                    .map(|bounds| Vector2::new(bounds.0.w, bounds.0.h))
+            })
+            .unwrap_or_else(Vector2::zeros);
+
+        Vector2::new(
+            size.width.resolve(available.x, auto.x),
+            size.height.resolve(available.y, auto.y),
+        )
+    }
+
+    /// The size a stacking container needs to fit its children, padding included
+    fn measure_children(&self, entity: Entity, available: Vector2<f32>) -> Option<Vector2<f32>> {
+        let container = self.containers.get(entity)?;
+        let direction = container.direction?;
+        let children = self.children.get(&entity)?;
+
+        let padding = container.padding;
+        let inner = Vector2::new(
+            (available.x - padding.horizontal()).max(0.0),
+            (available.y - padding.vertical()).max(0.0),
+        );
+
+        let mut total = Vector2::zeros();
+
+        for child in children {
+            let margin = self.margin(*child);
+            let size =
+                self.measure(*child, inner) + Vector2::new(margin.horizontal(), margin.vertical());
+
+            match direction {
+                Direction::Horizontal => {
+                    total.x += size.x;
+                    total.y = total.y.max(size.y);
+                }
+                Direction::Vertical => {
+                    total.x = total.x.max(size.x);
+                    total.y += size.y;
+                }
+            }
+        }
+
+        let spacing = container.spacing * (children.len().max(1) - 1) as f32;
+
+        match direction {
+            Direction::Horizontal => total.x += spacing,
+            Direction::Vertical => total.y += spacing,
+        }
+
+        Some(total + Vector2::new(padding.horizontal(), padding.vertical()))
+    }
+
+    /// Places the element in `parent` using its anchor, origin and margin
+    fn anchor(&self, entity: Entity, parent: Rect, size: Vector2<f32>) -> Rect {
+        let ui_element = self.ui_elements.get(entity);
+        let anchor = ui_element
+            .and_then(|ui_element| ui_element.anchor)
+            .unwrap_or_default();
+        let origin = ui_element
+            .and_then(|ui_element| ui_element.origin)
+            .unwrap_or_default();
+
+        let point = anchor.get_postion(self.margin(entity).shrink(parent))
+            - origin
+                .get_postion(Rect::new(0.0, 0.0, size.x, size.y))
+                .coords;
+
+        Rect::new(point.x, point.y, size.x, size.y)
+    }
+
+    /// Moves the element by its offsets and `Position`
+    fn offset(&self, entity: Entity, parent: Rect, mut rect: Rect) -> Rect {
+        if let Some(ui_element) = self.ui_elements.get(entity) {
+            rect.translate(
+                ui_element.offset
+                    + Vector2::new(
+                        ui_element.relative_offset.x * parent.w,
+                        ui_element.relative_offset.y * parent.h,
+                    ),
+            );
+        }
+
+        if let Some(position) = self.positions.get(entity) {
+            rect.translate(position.0.coords);
+        }
+
+        rect
+    }
+
+    fn place(&self, entity: Entity, rect: Rect, clip: Option<Rect>, placed: &mut Placed) {
+        placed.rects.push((entity, rect, clip));
+
+        if let Some(children) = self.children.get(&entity) {
+            let container = self.containers.get(entity);
+            let content = container
+                .map(|container| container.padding.shrink(rect))
+                .unwrap_or(rect);
+            let children_clip = match container {
+                Some(container) if container.clip => {
+                    Some(clip.map_or(content, |clip| intersect(clip, content)))
+                }
+                _ => clip,
+            };
+            let scroll = container.map_or(Vector2::zeros(), |container| container.scroll);
+            let scrolled = Rect::new(
+                content.x - scroll.x,
+                content.y - scroll.y,
+                content.w,
+                content.h,
+            );
+            let mut cursor = Point2::new(scrolled.x, scrolled.y);
+            let mut extent = Vector2::zeros();
+
+            for child in children {
+                let margin = self.margin(*child);
+                let size = self.measure(*child, Vector2::new(content.w, content.h));
+
+                let child_rect = if let Some(container) = container {
+                    if let Some(direction) = container.direction {
+                        let child_rect = Rect::new(
+                            cursor.x + margin.left,
+                            cursor.y + margin.top,
+                            size.x,
+                            size.y,
+                        );
+
+                        match direction {
+                            Direction::Horizontal => {
+                                cursor.x += margin.horizontal() + size.x + container.spacing
+                            }
+                            Direction::Vertical => {
+                                cursor.y += margin.vertical() + size.y + container.spacing
+                            }
+                        }
+
+                        child_rect
+                    } else {
+                        self.anchor(*child, scrolled, size)
+                    }
+                } else {
+                    self.anchor(*child, scrolled, size)
+                };
+                let child_rect = self.offset(*child, content, child_rect);
+
+                extent.x = extent
+                    .x
+                    .max(child_rect.x + child_rect.w + margin.right - scrolled.x);
+                extent.y = extent
+                    .y
+                    .max(child_rect.y + child_rect.h + margin.bottom - scrolled.y);
+
+                self.place(*child, child_rect, children_clip, placed);
+            }
+
+            placed.content_sizes.push((entity, extent));
+        }
+    }
+}
+
+/// Computes the `UiRect` of every `UiElement`, `Planet` runs this right before rendering
+pub struct UiLayout;
+
+impl<'a> System<'a> for UiLayout {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, ScreenSize>,
+        ReadStorage<'a, UiElement>,
+        ReadStorage<'a, UiParent>,
+        ReadStorage<'a, UiSize>,
+        ReadStorage<'a, UiContainer>,
+        ReadStorage<'a, Bounds>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Hidden>,
+        WriteStorage<'a, UiRect>,
+        WriteStorage<'a, ClipRect>,
+        WriteStorage<'a, ContentSize>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            screen_size,
+            ui_elements,
+            parents,
+            sizes,
+            containers,
+            bounds,
+            positions,
+            hiddens,
+            mut ui_rects,
+            mut clip_rects,
+            mut content_sizes,
+        ) = data;
+
+        let mut children: HashMap<Entity, Vec<Entity>> = HashMap::new();
+        let mut roots = Vec::new();
+
+        // Hidden elements are left out, and with them everything inside them
+        for (entity, _, parent, _) in (&*entities, &ui_elements, parents.maybe(), !&hiddens).join()
+        {
+            match parent {
+                Some(parent) if ui_elements.contains(parent.0) => {
+                    children.entry(parent.0).or_default().push(entity)
+                }
+                _ => roots.push(entity),
+            }
+        }
+
+        for siblings in children.values_mut() {
+            siblings.sort_by_key(|entity| {
+                let order = ui_elements
+                    .get(*entity)
+                    .map_or(0, |ui_element| ui_element.order);
+                (order, entity.id())
+            });
+        }
+
+        let layout = Layout {
+            ui_elements: &ui_elements,
+            sizes: &sizes,
+            containers: &containers,
+            bounds: &bounds,
+            positions: &positions,
+            children,
+        };
+
+        let screen = Rect::new(0.0, 0.0, screen_size.0.x, screen_size.0.y);
+        let mut placed = Placed::default();
+
+        for root in roots {
+            let size = layout.measure(root, screen_size.0);
+            let rect = layout.offset(root, screen, layout.anchor(root, screen, size));
+
+            layout.place(root, rect, None, &mut placed);
+        }
+
+        ui_rects.clear();
+        clip_rects.clear();
+        content_sizes.clear();
+
+        for (entity, rect, clip) in placed.rects {
+            let _ = ui_rects.insert(entity, UiRect(rect));
+
+            if let Some(clip) = clip {
+                let _ = clip_rects.insert(entity, ClipRect(clip));
+            }
+        }
+
+        for (entity, content_size) in placed.content_sizes {
+            let _ = content_sizes.insert(entity, ContentSize(content_size));
+        }
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    _dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.register::<UiSize>();
+    world.register::<UiParent>();
+    world.register::<UiContainer>();
+    world.register::<UiRect>();
+    world.register::<ClipRect>();
+    world.register::<ContentSize>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::Anchor;
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.register::<UiElement>();
+        world.register::<Hidden>();
+        world.register::<UiSize>();
+        world.register::<UiParent>();
+        world.register::<UiContainer>();
+        world.register::<UiRect>();
+        world.register::<ClipRect>();
+        world.register::<ContentSize>();
+        world.register::<Bounds>();
+        world.register::<Position>();
+        world.add_resource(ScreenSize(Vector2::new(200.0, 100.0)));
+        world
+    }
+
+    fn fixed(width: f32, height: f32) -> UiSize {
+        UiSize {
+            width: Size::Fixed(width),
+            height: Size::Fixed(height),
+        }
+    }
+
+    fn top_left() -> UiElement {
+        UiElement {
+            anchor: Some(Anchor::TopLeft),
+            origin: Some(Anchor::TopLeft),
+            ..Default::default()
+        }
+    }
+
+    fn stack(world: &mut World, container: UiContainer, size: UiSize) -> Entity {
+        world
+            .create_entity()
+            .with(top_left())
+            .with(container)
+            .with(size)
+            .build()
+    }
+
+    fn child(world: &mut World, parent: Entity, ui_element: UiElement, size: UiSize) -> Entity {
+        world
+            .create_entity()
+            .with(ui_element)
+            .with(size)
+            .with(UiParent(parent))
+            .build()
+    }
+
+    fn layout(world: &World) {
+        UiLayout.run_now(&world.res);
+    }
+
+    fn rect(world: &World, entity: Entity) -> Rect {
+        world.read_storage::<UiRect>().get(entity).unwrap().0
+    }
+
+    #[test]
+    fn anchors_with_margin() {
+        let mut world = world();
+        let corner = world
+            .create_entity()
+            .with(UiElement {
+                anchor: Some(Anchor::BottomRight),
+                origin: Some(Anchor::BottomRight),
+                margin: Edges::uniform(5.0),
+                ..Default::default()
+            })
+            .with(fixed(20.0, 10.0))
+            .build();
+        let centered = world
+            .create_entity()
+            .with(UiElement::default())
+            .with(fixed(20.0, 10.0))
+            .build();
+
+        layout(&world);
+
+        assert_eq!(rect(&world, corner), Rect::new(175.0, 85.0, 20.0, 10.0));
+        assert_eq!(rect(&world, centered), Rect::new(90.0, 45.0, 20.0, 10.0));
+    }
+
+    #[test]
+    fn percent_sizes_of_the_padded_content_area() {
+        let mut world = world();
+        let container = stack(
+            &mut world,
+            UiContainer {
+                direction: Some(Direction::Vertical),
+                padding: Edges::uniform(10.0),
+                ..Default::default()
+            },
+            fixed(100.0, 50.0),
+        );
+        let half = child(
+            &mut world,
+            container,
+            UiElement::default(),
+            UiSize {
+                width: Size::Percent(0.5),
+                height: Size::Percent(1.0),
+            },
+        );
+
+        layout(&world);
+
+        assert_eq!(rect(&world, half), Rect::new(10.0, 10.0, 40.0, 30.0));
+    }
+
+    #[test]
+    fn stacks_by_order_with_spacing() {
+        let mut world = world();
+        let container = stack(
+            &mut world,
+            UiContainer {
+                direction: Some(Direction::Vertical),
+                spacing: 5.0,
+                ..Default::default()
+            },
+            UiSize::default(),
+        );
+        let first = child(
+            &mut world,
+            container,
+            UiElement::default(),
+            fixed(30.0, 10.0),
+        );
+        let second = child(
+            &mut world,
+            container,
+            UiElement::default(),
+            fixed(20.0, 10.0),
+        );
+        let before_both = child(
+            &mut world,
+            container,
+            UiElement {
+                order: -1,
+                ..Default::default()
+            },
+            fixed(10.0, 10.0),
+        );
+
+        layout(&world);
+
+        assert_eq!(rect(&world, before_both).y, 0.0);
+        assert_eq!(rect(&world, first).y, 15.0);
+        assert_eq!(rect(&world, second).y, 30.0);
+        // Auto sized to fit the children and the spacing between them
+        assert_eq!(rect(&world, container), Rect::new(0.0, 0.0, 30.0, 40.0));
+    }
+}
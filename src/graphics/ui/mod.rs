@@ -0,0 +1,51 @@
+pub mod layout;
+
+use self::layout::Edges;
+use super::Anchor;
+use ggez::{nalgebra::Vector2, Context};
+use smart_default::SmartDefault;
+use specs::prelude::*;
+
+/// Laid out in screen space by `UiLayout`. A `Position` on the entity
+/// moves it from where it was laid out, along with its children
+#[derive(SmartDefault)]
+pub struct UiElement {
+    /// Where in the parent's content area, or the screen, the element is anchored
+    pub anchor: Option<Anchor>,
+    /// Which point of the element sits on the anchor
+    pub origin: Option<Anchor>,
+    /// Space kept clear around the element
+    pub margin: Edges,
+    /// Moves the element by this many pixels
+    #[default(Vector2::zeros())]
+    pub offset: Vector2<f32>,
+    /// Moves the element by a fraction of the parent's content area, or the screen
+    #[default(Vector2::zeros())]
+    pub relative_offset: Vector2<f32>,
+    /// Where the element goes among its siblings in a stacking container, lowest first.
+    /// Ties go by entity id, which stops matching creation order once ids get reused
+    pub order: i32,
+}
+
+impl Component for UiElement {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Leaves the element, and everything inside it, out of the layout so it isn't drawn
+#[derive(Default)]
+pub struct Hidden;
+
+impl Component for Hidden {
+    type Storage = NullStorage<Self>;
+}
+
+pub fn setup<'a, 'b>(
+    ctx: &mut Context,
+    world: &mut World,
+    dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.register::<UiElement>();
+    world.register::<Hidden>();
+
+    layout::setup(ctx, world, dispatcher_builder);
+}
@@ -0,0 +1,61 @@
+use super::MousePosition;
+use crate::graphics::Position;
+use ggez::{input::mouse, Context, GameResult};
+use smart_default::SmartDefault;
+use specs::prelude::*;
+
+/// How the OS cursor should behave, `Planet` applies this whenever it changes
+#[derive(Copy, Clone, PartialEq, Debug, SmartDefault)]
+pub struct CursorSettings {
+    #[default = true]
+    pub visible: bool,
+    /// Keeps the cursor inside the window
+    pub grabbed: bool,
+    /// Hides and grabs the cursor regardless of `visible` and `grabbed`,
+    /// for when only `MouseMotion` matters, like with a first person camera
+    pub relative: bool,
+}
+
+impl CursorSettings {
+    pub fn apply(&self, ctx: &mut Context) -> GameResult {
+        mouse::set_cursor_hidden(ctx, self.relative || !self.visible);
+        mouse::set_cursor_grabbed(ctx, self.relative || self.grabbed)
+    }
+}
+
+/// Moves the entity to the mouse every frame, for drawing a custom cursor.
+/// Pair it with a top left anchored `UiElement` and a high `Layer` so it's drawn on top,
+/// and probably hide the OS cursor with `CursorSettings`
+#[derive(Default)]
+pub struct Cursor;
+
+impl Component for Cursor {
+    type Storage = NullStorage<Self>;
+}
+
+pub struct CursorFollower;
+
+impl<'a> System<'a> for CursorFollower {
+    type SystemData = (
+        Read<'a, MousePosition>,
+        ReadStorage<'a, Cursor>,
+        WriteStorage<'a, Position>,
+    );
+
+    fn run(&mut self, (mouse_position, cursors, mut positions): Self::SystemData) {
+        for (_, position) in (&cursors, &mut positions).join() {
+            position.0 = mouse_position.0;
+        }
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.add_resource(CursorSettings::default());
+    world.register::<Cursor>();
+
+    dispatcher_builder.add(CursorFollower, "cursor_follower", &[]);
+}
@@ -0,0 +1,252 @@
+pub mod cursor;
+pub mod picking;
+pub mod recording;
+pub mod touch;
+
+use self::touch::TouchPhase;
+use ggez::{
+    event::Button,
+    input::{
+        keyboard::{KeyCode, KeyMods},
+        mouse::MouseButton,
+    },
+    nalgebra::{Point2, Vector2},
+    Context,
+};
+use serde::{Deserialize, Serialize};
+use smart_default::SmartDefault;
+use specs::{prelude::*, shrev::EventChannel};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    time::Duration,
+};
+
+#[derive(SmartDefault)]
+pub struct MousePosition(#[default(Point2::new(0.0, 0.0))] pub Point2<f32>);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputState {
+    Pressed,
+    Held,
+    Released,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InputResource<K: Hash + Eq> {
+    states: HashMap<K, InputState>,
+    pressed: HashSet<K>,
+    released: HashSet<K>,
+    events: Vec<(K, InputState)>,
+}
+
+impl<K: Hash + Eq + Copy> Default for InputResource<K> {
+    fn default() -> Self {
+        Self {
+            states: HashMap::new(),
+            pressed: HashSet::new(),
+            released: HashSet::new(),
+            events: Vec::new(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Copy> InputResource<K> {
+    /// Marks the key specified as pressed down this frame
+    pub fn press(&mut self, input: K) {
+        self.states.insert(input, InputState::Pressed);
+        self.pressed.insert(input);
+        self.events.push((input, InputState::Pressed));
+    }
+
+    /// Marks the key specified as released this frame
+    pub fn release(&mut self, input: K) {
+        self.states.insert(input, InputState::Released);
+        self.released.insert(input);
+        self.events.push((input, InputState::Released));
+    }
+
+    pub fn update(&mut self) {
+        self.states = self
+            .states
+            .iter()
+            .filter_map(|(key, state)| match state {
+                InputState::Released => None,
+                _ => Some((*key, InputState::Held)),
+            })
+            .collect::<HashMap<K, InputState>>();
+
+        self.pressed.clear();
+        self.released.clear();
+        self.events.clear();
+    }
+
+    /// Adds the presses and releases from `other` on top of the ones here
+    /// and takes on its current states, so edges carry over until `update`
+    pub fn latch(&mut self, other: &Self) {
+        self.states = other.states.clone();
+        self.pressed.extend(other.pressed.iter().cloned());
+        self.released.extend(other.released.iter().cloned());
+        self.events.extend(other.events.iter().cloned());
+    }
+
+    /// The same states, presses and releases with every key converted by `convert`
+    pub fn map<L: Hash + Eq + Copy>(&self, convert: impl Fn(K) -> L) -> InputResource<L> {
+        InputResource {
+            states: self
+                .states
+                .iter()
+                .map(|(key, state)| (convert(*key), state.clone()))
+                .collect(),
+            pressed: self.pressed.iter().map(|key| convert(*key)).collect(),
+            released: self.released.iter().map(|key| convert(*key)).collect(),
+            events: self
+                .events
+                .iter()
+                .map(|(key, state)| (convert(*key), state.clone()))
+                .collect(),
+        }
+    }
+
+    /// Returns true if the key specified was pressed down this frame or is being held
+    #[allow(dead_code)]
+    pub fn is_down(&self, input: &K) -> bool {
+        self.pressed.contains(input)
+            || self
+                .states
+                .get(input)
+                .filter(|state| **state != InputState::Released)
+                .is_some()
+    }
+
+    /// Returns true if the key specified was pressed down this frame,
+    /// even if it was released again before the frame ended
+    #[allow(dead_code)]
+    pub fn is_pressed(&self, input: &K) -> bool {
+        self.pressed.contains(input)
+    }
+
+    /// Returns true if the key specified is held this frame
+    #[allow(dead_code)]
+    pub fn is_held(&self, input: &K) -> bool {
+        self.states
+            .get(input)
+            .filter(|state| **state == InputState::Held)
+            .is_some()
+    }
+
+    /// Returns true if the key specified was released this frame,
+    /// even if it was only pressed down this frame
+    #[allow(dead_code)]
+    pub fn is_released(&self, input: &K) -> bool {
+        self.released.contains(input)
+    }
+
+    /// Every press and release that happened this frame, in the order they happened
+    #[allow(dead_code)]
+    pub fn events(&self) -> &[(K, InputState)] {
+        &self.events
+    }
+}
+
+pub type Keys = InputResource<KeyCode>;
+
+pub type MouseButtons = InputResource<MouseButton>;
+
+/// The buttons of every connected gamepad, merged together
+pub type GamepadButtons = InputResource<Button>;
+
+#[derive(Default)]
+pub struct MouseMotion(pub Option<(Vector2<f32>)>);
+
+#[derive(Default)]
+pub struct MouseWheel(pub Option<(Vector2<f32>)>);
+
+#[derive(Clone, Debug)]
+pub enum InputEvent {
+    KeyDown {
+        keycode: KeyCode,
+        keymods: KeyMods,
+        repeat: bool,
+    },
+    KeyUp {
+        keycode: KeyCode,
+        keymods: KeyMods,
+    },
+    MouseButtonDown {
+        button: MouseButton,
+        position: Point2<f32>,
+    },
+    MouseButtonUp {
+        button: MouseButton,
+        position: Point2<f32>,
+    },
+    MouseMotion {
+        position: Point2<f32>,
+        delta: Vector2<f32>,
+    },
+    MouseWheel(Vector2<f32>),
+    Touch {
+        id: u64,
+        phase: TouchPhase,
+        position: Point2<f32>,
+    },
+    GamepadButtonDown(Button),
+    GamepadButtonUp(Button),
+    TextInput(char),
+    Resize(Vector2<f32>),
+}
+
+/// An input event along with when it happened, measured from the start of the game
+#[derive(Clone, Debug)]
+pub struct TimedInputEvent {
+    pub time: Duration,
+    pub event: InputEvent,
+}
+
+/// Every input event in the order ggez delivered it.
+/// Systems that care about ordering, or about events that polling would miss,
+/// should `register_reader` on this in their `setup` and read from it every frame
+pub type InputEvents = EventChannel<TimedInputEvent>;
+
+pub fn setup<'a, 'b>(
+    ctx: &mut Context,
+    world: &mut World,
+    dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.add_resource(MousePosition::default());
+    world.add_resource(Keys::default());
+    world.add_resource(MouseButtons::default());
+    world.add_resource(GamepadButtons::default());
+    world.add_resource(MouseMotion::default());
+    world.add_resource(MouseWheel::default());
+    world.add_resource(InputEvents::new());
+
+    cursor::setup(ctx, world, dispatcher_builder);
+    picking::setup(ctx, world, dispatcher_builder);
+    touch::setup(ctx, world, dispatcher_builder);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn press_and_release_in_one_frame() {
+        let mut keys = Keys::default();
+
+        keys.press(KeyCode::A);
+        keys.release(KeyCode::A);
+
+        assert!(keys.is_pressed(&KeyCode::A));
+        assert!(keys.is_released(&KeyCode::A));
+        assert!(keys.is_down(&KeyCode::A));
+
+        keys.update();
+
+        assert!(!keys.is_pressed(&KeyCode::A));
+        assert!(!keys.is_released(&KeyCode::A));
+        assert!(!keys.is_down(&KeyCode::A));
+        assert!(keys.events().is_empty());
+    }
+}
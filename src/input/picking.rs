@@ -0,0 +1,170 @@
+use super::{MouseButtons, MousePosition};
+use crate::graphics::{
+    camera::{screen_to_world, ActiveCamera, Camera},
+    rendering::Bounds,
+    ui::UiElement,
+    Layer, Position, ScreenSize,
+};
+use ggez::{input::mouse::MouseButton, Context};
+use specs::prelude::*;
+use std::cmp::Reverse;
+
+/// Lets the mouse pick the entity, using the `Bounds` it was last rendered with
+#[derive(Default)]
+pub struct Pickable;
+
+impl Component for Pickable {
+    type Storage = NullStorage<Self>;
+}
+
+/// Added to the topmost pickable entity under the mouse
+#[derive(Default)]
+pub struct Hovered;
+
+impl Component for Hovered {
+    type Storage = NullStorage<Self>;
+}
+
+/// Added while the left mouse button is held after being pressed on the entity
+#[derive(Default)]
+pub struct Pressed;
+
+impl Component for Pressed {
+    type Storage = NullStorage<Self>;
+}
+
+/// Added for a single frame when the left mouse button
+/// is released over the same entity it was pressed on
+#[derive(Default)]
+pub struct Clicked;
+
+impl Component for Clicked {
+    type Storage = NullStorage<Self>;
+}
+
+/// Every pickable entity under the mouse, topmost first
+#[derive(Default)]
+pub struct EntitiesUnderMouse(pub Vec<Entity>);
+
+#[derive(Default)]
+pub struct Picking {
+    pressed_entity: Option<Entity>,
+}
+
+impl<'a> System<'a> for Picking {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, MousePosition>,
+        Read<'a, MouseButtons>,
+        Read<'a, ScreenSize>,
+        Read<'a, ActiveCamera>,
+        Write<'a, EntitiesUnderMouse>,
+        ReadStorage<'a, Pickable>,
+        ReadStorage<'a, Bounds>,
+        ReadStorage<'a, Layer>,
+        ReadStorage<'a, UiElement>,
+        ReadStorage<'a, Camera>,
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, Hovered>,
+        WriteStorage<'a, Pressed>,
+        WriteStorage<'a, Clicked>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mouse_position,
+            mouse_buttons,
+            screen_size,
+            active_camera,
+            mut entities_under_mouse,
+            pickables,
+            bounds,
+            layers,
+            ui_elements,
+            cameras,
+            positions,
+            mut hovereds,
+            mut presseds,
+            mut clickeds,
+        ) = data;
+
+        let mouse_position = mouse_position.0;
+
+        // UI elements are picked in screen space, everything else in world space
+        let world_mouse_position = active_camera.0.and_then(|active_camera| {
+            let camera = cameras.get(active_camera)?;
+            let camera_position = positions.get(active_camera)?;
+
+            Some(screen_to_world(
+                mouse_position,
+                camera_position.0,
+                camera.zoom,
+                screen_size.0,
+            ))
+        });
+
+        let mut under_mouse: Vec<(Entity, i32)> = (&*entities, &pickables, &bounds)
+            .join()
+            .filter(|(entity, _, area)| {
+                if ui_elements.contains(*entity) {
+                    area.0.contains(mouse_position)
+                } else {
+                    world_mouse_position.map_or(false, |position| area.0.contains(position))
+                }
+            })
+            .map(|(entity, _, _)| {
+                let layer = layers.get(entity).map(|layer| layer.0).unwrap_or_default();
+
+                (entity, layer)
+            })
+            .collect();
+
+        // Same order the renderer draws in, just reversed
+        under_mouse.sort_by_key(|(entity, layer)| Reverse((*layer, entity.id())));
+
+        entities_under_mouse.0 = under_mouse.into_iter().map(|(entity, _)| entity).collect();
+
+        let hovered_entity = entities_under_mouse.0.first().cloned();
+
+        hovereds.clear();
+        if let Some(hovered_entity) = hovered_entity {
+            let _ = hovereds.insert(hovered_entity, Hovered);
+        }
+
+        clickeds.clear();
+        if mouse_buttons.is_pressed(&MouseButton::Left) {
+            self.pressed_entity = hovered_entity;
+        }
+        if mouse_buttons.is_released(&MouseButton::Left) {
+            if let Some(pressed_entity) = self.pressed_entity.take() {
+                if hovered_entity == Some(pressed_entity) {
+                    let _ = clickeds.insert(pressed_entity, Clicked);
+                }
+            }
+        }
+
+        presseds.clear();
+        if let Some(pressed_entity) = self.pressed_entity {
+            if entities.is_alive(pressed_entity) {
+                let _ = presseds.insert(pressed_entity, Pressed);
+            }
+        }
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.register::<Pickable>();
+    world.register::<Hovered>();
+    world.register::<Pressed>();
+    world.register::<Clicked>();
+
+    world.add_resource(EntitiesUnderMouse::default());
+
+    dispatcher_builder.add(Picking::default(), "picking", &[]);
+}
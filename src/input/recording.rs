@@ -0,0 +1,263 @@
+use super::{
+    GamepadButtons, InputResource, Keys, MouseButtons, MouseMotion, MousePosition, MouseWheel,
+};
+use crate::DeltaTime;
+use ggez::{
+    event::Button,
+    nalgebra::{Point2, Vector2},
+};
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use std::{fs, io, path::Path};
+
+macro_rules! recorded_button {
+    ($($button:ident),*) => {
+        /// gilrs' `Button` can't be serialized, so recordings store this copy of it instead
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+        pub enum RecordedButton {
+            $($button),*
+        }
+
+        impl From<Button> for RecordedButton {
+            fn from(button: Button) -> Self {
+                match button {
+                    $(Button::$button => RecordedButton::$button),*
+                }
+            }
+        }
+
+        impl From<RecordedButton> for Button {
+            fn from(button: RecordedButton) -> Self {
+                match button {
+                    $(RecordedButton::$button => Button::$button),*
+                }
+            }
+        }
+    };
+}
+
+recorded_button!(
+    South,
+    East,
+    North,
+    West,
+    C,
+    Z,
+    LeftTrigger,
+    LeftTrigger2,
+    RightTrigger,
+    RightTrigger2,
+    Select,
+    Start,
+    Mode,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    Unknown
+);
+
+/// A snapshot of all the input state `Planet` writes in a single frame
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InputFrame {
+    pub delta_time: f32,
+    pub keys: Keys,
+    pub mouse_buttons: MouseButtons,
+    /// Missing from recordings made before gamepads were recorded
+    #[serde(default)]
+    pub gamepad_buttons: InputResource<RecordedButton>,
+    pub mouse_position: (f32, f32),
+    pub mouse_motion: Option<(f32, f32)>,
+    pub mouse_wheel: Option<(f32, f32)>,
+}
+
+impl InputFrame {
+    pub fn capture(res: &Resources) -> Self {
+        let mouse_position = res.fetch::<MousePosition>().0;
+
+        Self {
+            delta_time: res.fetch::<DeltaTime>().0,
+            keys: res.fetch::<Keys>().clone(),
+            mouse_buttons: res.fetch::<MouseButtons>().clone(),
+            gamepad_buttons: res.fetch::<GamepadButtons>().map(RecordedButton::from),
+            mouse_position: (mouse_position.x, mouse_position.y),
+            mouse_motion: res
+                .fetch::<MouseMotion>()
+                .0
+                .map(|motion| (motion.x, motion.y)),
+            mouse_wheel: res.fetch::<MouseWheel>().0.map(|wheel| (wheel.x, wheel.y)),
+        }
+    }
+
+    /// Overwrites the input state in `res` with this frame,
+    /// adding any of the resources that aren't there yet
+    pub fn apply(&self, res: &mut Resources) {
+        let (mouse_x, mouse_y) = self.mouse_position;
+
+        res.insert(DeltaTime(self.delta_time));
+        res.insert(self.keys.clone());
+        res.insert(self.mouse_buttons.clone());
+        res.insert(self.gamepad_buttons.map(Button::from));
+        res.insert(MousePosition(Point2::new(mouse_x, mouse_y)));
+        res.insert(MouseMotion(
+            self.mouse_motion.map(|(x, y)| Vector2::new(x, y)),
+        ));
+        res.insert(MouseWheel(
+            self.mouse_wheel.map(|(x, y)| Vector2::new(x, y)),
+        ));
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct InputRecording {
+    pub frames: Vec<InputFrame>,
+}
+
+impl InputRecording {
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let serialized = ron::ser::to_string(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+
+        fs::write(path, serialized)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let serialized = fs::read_to_string(path)?;
+
+        ron::de::from_str(&serialized)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+    }
+}
+
+/// Feeds a recording back one frame at a time in place of live input
+pub struct InputPlayer {
+    recording: InputRecording,
+    next_frame: usize,
+}
+
+impl InputPlayer {
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            recording,
+            next_frame: 0,
+        }
+    }
+
+    /// Applies the next recorded frame to `res`,
+    /// returns false once there are no frames left
+    pub fn play_frame(&mut self, res: &mut Resources) -> bool {
+        match self.recording.frames.get(self.next_frame) {
+            Some(frame) => {
+                frame.apply(res);
+                self.next_frame += 1;
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_frame >= self.recording.frames.len()
+    }
+
+    /// Runs the whole recording through `dispatcher` without a window or a `Context`.
+    /// Anything the systems need besides input, like `ScreenSize`, has to already be in `world`
+    pub fn run_headless(&mut self, world: &mut World, dispatcher: &mut Dispatcher) {
+        while self.play_frame(&mut world.res) {
+            dispatcher.dispatch(&world.res);
+            world.maintain();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ggez::input::keyboard::KeyCode;
+
+    #[derive(Default)]
+    struct Observed {
+        presses: usize,
+        mouse_position: Option<Point2<f32>>,
+    }
+
+    struct Observer;
+
+    impl<'a> System<'a> for Observer {
+        type SystemData = (Read<'a, Keys>, Read<'a, MousePosition>, Write<'a, Observed>);
+
+        fn run(&mut self, (keys, mouse_position, mut observed): Self::SystemData) {
+            if keys.is_pressed(&KeyCode::Space) {
+                observed.presses += 1;
+            }
+
+            observed.mouse_position = Some(mouse_position.0);
+        }
+    }
+
+    /// Three frames with space and south pressed on the second and the mouse moving right
+    fn record() -> InputRecording {
+        let mut world = World::new();
+        world.add_resource(DeltaTime(1.0 / 60.0));
+        world.add_resource(Keys::default());
+        world.add_resource(MouseButtons::default());
+        world.add_resource(GamepadButtons::default());
+        world.add_resource(MousePosition::default());
+        world.add_resource(MouseMotion::default());
+        world.add_resource(MouseWheel::default());
+
+        let mut recording = InputRecording::default();
+
+        for frame in 0..3 {
+            if frame == 1 {
+                world.write_resource::<Keys>().press(KeyCode::Space);
+                world
+                    .write_resource::<GamepadButtons>()
+                    .press(Button::South);
+            }
+
+            world.write_resource::<MousePosition>().0 = Point2::new(frame as f32 * 10.0, 5.0);
+            recording.frames.push(InputFrame::capture(&world.res));
+            world.write_resource::<Keys>().update();
+            world.write_resource::<GamepadButtons>().update();
+        }
+
+        recording
+    }
+
+    #[test]
+    fn replays_headlessly_after_saving_and_loading() {
+        let path = std::env::temp_dir().join("ggez_planet_recording_test.ron");
+
+        record().save(&path).unwrap();
+        let recording = InputRecording::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(recording.frames.len(), 3);
+
+        let mut world = World::new();
+        let mut dispatcher = DispatcherBuilder::new()
+            .with(Observer, "observer", &[])
+            .build();
+        dispatcher.setup(&mut world.res);
+
+        let mut player = InputPlayer::new(recording);
+        player.run_headless(&mut world, &mut dispatcher);
+
+        assert!(player.is_finished());
+
+        let observed = world.read_resource::<Observed>();
+        assert_eq!(observed.presses, 1);
+        assert_eq!(observed.mouse_position, Some(Point2::new(20.0, 5.0)));
+
+        let keys = world.read_resource::<Keys>();
+        assert!(keys.is_held(&KeyCode::Space));
+        assert!(!keys.is_pressed(&KeyCode::Space));
+
+        let gamepad_buttons = world.read_resource::<GamepadButtons>();
+        assert!(gamepad_buttons.is_held(&Button::South));
+    }
+}
@@ -0,0 +1,281 @@
+use ggez::{
+    nalgebra::{Point2, Vector2},
+    Context,
+};
+use specs::prelude::*;
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TouchPhase {
+    Started,
+    Moved,
+    Ended,
+    Cancelled,
+}
+
+#[derive(Clone, Debug)]
+pub struct Touch {
+    pub position: Point2<f32>,
+    /// How far the finger moved this frame
+    pub delta: Vector2<f32>,
+    /// Mirrors `InputState`, `Started` the frame the finger touched down, `Moved` while
+    /// it stays down and `Ended` or `Cancelled` the frame it lifted, until the next `update`
+    pub phase: TouchPhase,
+    /// Whether the finger touched down this frame
+    pub pressed: bool,
+    /// Whether the finger lifted this frame, even if it only touched down this frame
+    pub released: bool,
+}
+
+/// Every finger on the screen, keyed by the id the platform gave it
+#[derive(Default)]
+pub struct Touches {
+    touches: HashMap<u64, Touch>,
+    /// Fingers that lifted this frame and had their id reused by a new one straight away
+    ended: Vec<(u64, Touch)>,
+}
+
+impl Touches {
+    /// Feeds a touch event in, whether it came from the window or was made up
+    pub fn touch(&mut self, id: u64, phase: TouchPhase, position: Point2<f32>) {
+        match phase {
+            TouchPhase::Started => {
+                let touch = Touch {
+                    position,
+                    delta: Vector2::zeros(),
+                    phase,
+                    pressed: true,
+                    released: false,
+                };
+
+                if let Some(previous) = self.touches.insert(id, touch) {
+                    // Keep the release around for the rest of the frame
+                    if previous.released {
+                        self.ended.push((id, previous));
+                    }
+                }
+            }
+            TouchPhase::Moved | TouchPhase::Ended | TouchPhase::Cancelled => {
+                if let Some(touch) = self.touches.get_mut(&id) {
+                    touch.delta += position - touch.position;
+                    touch.position = position;
+
+                    if phase != TouchPhase::Moved {
+                        touch.phase = phase;
+                        touch.released = true;
+                    } else if !touch.pressed {
+                        touch.phase = phase;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn update(&mut self) {
+        self.ended.clear();
+        self.touches.retain(|_, touch| !touch.released);
+
+        for touch in self.touches.values_mut() {
+            touch.phase = TouchPhase::Moved;
+            touch.pressed = false;
+            touch.delta = Vector2::zeros();
+        }
+    }
+
+    /// The latest finger with the id
+    pub fn get(&self, id: u64) -> Option<&Touch> {
+        self.touches.get(&id)
+    }
+
+    /// Every finger on the screen, along with the ones that lifted this frame
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &Touch)> {
+        self.touches
+            .iter()
+            .map(|(id, touch)| (*id, touch))
+            .chain(self.ended.iter().map(|(id, touch)| (*id, touch)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.touches.len() + self.ended.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.touches.is_empty() && self.ended.is_empty()
+    }
+}
+
+/// Two finger gestures recognized this frame
+#[derive(Default)]
+pub struct Gestures {
+    /// How much the distance between the two fingers was scaled by this frame
+    pub pinch: Option<f32>,
+    /// How far the two fingers moved together this frame, on average
+    pub pan: Option<Vector2<f32>>,
+}
+
+#[derive(Default)]
+pub struct GestureRecognizer;
+
+impl<'a> System<'a> for GestureRecognizer {
+    type SystemData = (Read<'a, Touches>, Write<'a, Gestures>);
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (touches, mut gestures) = data;
+
+        gestures.pinch = None;
+        gestures.pan = None;
+
+        let fingers: Vec<&Touch> = touches
+            .iter()
+            .map(|(_, touch)| touch)
+            .filter(|touch| !touch.released)
+            .collect();
+
+        if fingers.len() != 2 {
+            return;
+        }
+
+        let (first, second) = (fingers[0], fingers[1]);
+
+        let distance = (first.position - second.position).norm();
+        let previous_distance =
+            ((first.position - first.delta) - (second.position - second.delta)).norm();
+
+        if previous_distance > 0.0 && distance != previous_distance {
+            gestures.pinch = Some(distance / previous_distance);
+        }
+
+        let pan = (first.delta + second.delta) / 2.0;
+
+        if pan != Vector2::zeros() {
+            gestures.pan = Some(pan);
+        }
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.add_resource(Touches::default());
+    world.add_resource(Gestures::default());
+
+    dispatcher_builder.add(GestureRecognizer, "gesture_recognizer", &[]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tap_in_one_frame() {
+        let mut touches = Touches::default();
+        touches.touch(0, TouchPhase::Started, Point2::new(10.0, 10.0));
+        touches.touch(0, TouchPhase::Ended, Point2::new(10.0, 10.0));
+
+        let touch = touches.get(0).unwrap();
+        assert!(touch.pressed);
+        assert!(touch.released);
+        assert_eq!(touch.phase, TouchPhase::Ended);
+
+        touches.update();
+
+        assert!(touches.is_empty());
+    }
+
+    #[test]
+    fn held_touch_moves() {
+        let mut touches = Touches::default();
+        touches.touch(0, TouchPhase::Started, Point2::new(10.0, 10.0));
+        touches.update();
+        touches.touch(0, TouchPhase::Moved, Point2::new(15.0, 10.0));
+
+        let touch = touches.get(0).unwrap();
+        assert!(!touch.pressed);
+        assert!(!touch.released);
+        assert_eq!(touch.phase, TouchPhase::Moved);
+        assert_eq!(touch.position, Point2::new(15.0, 10.0));
+        assert_eq!(touch.delta, Vector2::new(5.0, 0.0));
+    }
+
+    #[test]
+    fn id_reused_in_the_frame_it_ended() {
+        let mut touches = Touches::default();
+        touches.touch(0, TouchPhase::Started, Point2::new(10.0, 10.0));
+        touches.update();
+        touches.touch(0, TouchPhase::Ended, Point2::new(10.0, 10.0));
+        touches.touch(0, TouchPhase::Started, Point2::new(50.0, 50.0));
+
+        assert_eq!(touches.len(), 2);
+        assert!(touches
+            .iter()
+            .any(|(id, touch)| id == 0 && touch.released && touch.phase == TouchPhase::Ended));
+
+        let touch = touches.get(0).unwrap();
+        assert!(touch.pressed);
+        assert_eq!(touch.phase, TouchPhase::Started);
+        assert_eq!(touch.position, Point2::new(50.0, 50.0));
+
+        touches.update();
+
+        assert_eq!(touches.len(), 1);
+        assert_eq!(touches.get(0).unwrap().phase, TouchPhase::Moved);
+    }
+
+    fn recognize(touches: Touches) -> (Option<f32>, Option<Vector2<f32>>) {
+        let mut world = World::new();
+        world.add_resource(touches);
+        world.add_resource(Gestures::default());
+
+        GestureRecognizer.run_now(&world.res);
+
+        let gestures = world.read_resource::<Gestures>();
+        (gestures.pinch, gestures.pan)
+    }
+
+    fn two_fingers() -> Touches {
+        let mut touches = Touches::default();
+        touches.touch(0, TouchPhase::Started, Point2::new(0.0, 0.0));
+        touches.touch(1, TouchPhase::Started, Point2::new(10.0, 0.0));
+        touches.update();
+        touches
+    }
+
+    #[test]
+    fn pinch() {
+        let mut touches = two_fingers();
+        touches.touch(0, TouchPhase::Moved, Point2::new(-5.0, 0.0));
+        touches.touch(1, TouchPhase::Moved, Point2::new(15.0, 0.0));
+
+        let (pinch, pan) = recognize(touches);
+
+        assert_eq!(pinch, Some(2.0));
+        assert_eq!(pan, None);
+    }
+
+    #[test]
+    fn pan() {
+        let mut touches = two_fingers();
+        touches.touch(0, TouchPhase::Moved, Point2::new(0.0, 4.0));
+        touches.touch(1, TouchPhase::Moved, Point2::new(10.0, 4.0));
+
+        let (pinch, pan) = recognize(touches);
+
+        assert_eq!(pinch, None);
+        assert_eq!(pan, Some(Vector2::new(0.0, 4.0)));
+    }
+
+    #[test]
+    fn one_finger_is_not_a_gesture() {
+        let mut touches = Touches::default();
+        touches.touch(0, TouchPhase::Started, Point2::new(0.0, 0.0));
+        touches.update();
+        touches.touch(0, TouchPhase::Moved, Point2::new(5.0, 5.0));
+
+        let (pinch, pan) = recognize(touches);
+
+        assert_eq!(pinch, None);
+        assert_eq!(pan, None);
+    }
+}
@@ -1,53 +1,123 @@
 #![feature(duration_float)]
 
+pub mod commands;
 pub mod debug;
 pub mod graphics;
 pub mod input;
+pub mod plugin;
 pub mod renderers;
+pub mod state;
+pub mod timestep;
+pub mod widgets;
+pub mod window;
 
 use crate::{
-    graphics::{rendering::RenderingSystem, ScreenSize},
-    input::{InputState, Keys, MouseButtons, MouseMotion, MousePosition, MouseWheel},
+    debug::console::{self, DebugConsole},
+    graphics::{
+        rendering::RenderingSystem,
+        settings::{draw_letterbox, RenderSettings, Viewport},
+        text::TextMetrics,
+        ui::layout::UiLayout,
+        Fonts, ScreenSize,
+    },
+    input::{
+        cursor::CursorSettings,
+        recording::{InputFrame, InputPlayer, InputRecording},
+        touch::{TouchPhase, Touches},
+        GamepadButtons, InputEvent, InputEvents, Keys, MouseButtons, MouseMotion, MousePosition,
+        MouseWheel, TimedInputEvent,
+    },
+    plugin::PlanetBuilder,
+    state::StateMachine,
+    timestep::FixedStepper,
+    window::{PreventQuit, QuitRequest, WindowEvent, WindowEvents, WindowState},
 };
 use ggez::{
-    event,
-    graphics::{self as ggez_graphics, Rect},
+    event::{self, Button},
+    graphics::{self as ggez_graphics, Canvas},
     input::{
         keyboard::{KeyCode, KeyMods},
         mouse::{self, MouseButton},
     },
-    nalgebra::Vector2,
+    nalgebra::{Point2, Vector2},
     timer, Context, GameResult,
 };
 use specs::{prelude::*, shred::RunNow};
+use std::{
+    mem,
+    time::{Duration, Instant},
+};
 
 #[derive(Default)]
 pub struct DeltaTime(pub f32);
 
+/// The player's input, kept away from systems while the debug console is open
+struct HiddenInput {
+    keys: Keys,
+    gamepad_buttons: GamepadButtons,
+    mouse_buttons: MouseButtons,
+    mouse_motion: MouseMotion,
+    mouse_wheel: MouseWheel,
+}
+
+impl HiddenInput {
+    fn restore(self, world: &World) {
+        *world.write_resource::<Keys>() = self.keys;
+        *world.write_resource::<GamepadButtons>() = self.gamepad_buttons;
+        *world.write_resource::<MouseButtons>() = self.mouse_buttons;
+        *world.write_resource::<MouseMotion>() = self.mouse_motion;
+        *world.write_resource::<MouseWheel>() = self.mouse_wheel;
+    }
+}
+
+fn take_resource<T: Default + Send + Sync + 'static>(world: &World) -> T {
+    mem::replace(&mut *world.write_resource::<T>(), T::default())
+}
+
 pub struct Planet<'a, 'b> {
     world: World,
     dispatcher: Dispatcher<'a, 'b>,
+    input_recording: Option<InputRecording>,
+    input_player: Option<InputPlayer>,
+    fixed_stepper: Option<FixedStepper<'a, 'b>>,
+    states: StateMachine<'a, 'b>,
+    window_size: Vector2<f32>,
+    last_frame: Instant,
+    cursor_settings: Option<CursorSettings>,
+    clip_canvas: Option<Canvas>,
 }
 
 impl<'a, 'b> Planet<'a, 'b> {
+    /// Creates a planet with all the default plugins,
+    /// use a `PlanetBuilder` to pick the plugins yourself
     pub fn new(
         ctx: &mut Context,
-        mut world: World,
-        mut dispatcher_builder: DispatcherBuilder<'a, 'b>,
-    ) -> Self {
-        world.add_resource(DeltaTime::default());
+        world: World,
+        dispatcher_builder: DispatcherBuilder<'a, 'b>,
+    ) -> GameResult<Self> {
+        PlanetBuilder::new(world, dispatcher_builder)
+            .with_default_plugins()
+            .build(ctx)
+    }
 
-        graphics::setup(ctx, &mut world, &mut dispatcher_builder);
-        input::setup(ctx, &mut world, &mut dispatcher_builder);
+    /// Starts recording the input of every frame, throwing away any recording in progress
+    pub fn start_recording(&mut self) {
+        self.input_recording = Some(InputRecording::default());
+    }
 
-        renderers::setup(ctx, &mut world, &mut dispatcher_builder);
+    /// Stops recording and returns everything recorded so far
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        self.input_recording.take()
+    }
 
-        debug::setup(ctx, &mut world, &mut dispatcher_builder);
+    /// Plays `recording` back in place of live input until it runs out,
+    /// live input events are dropped in the meantime
+    pub fn replay(&mut self, recording: InputRecording) {
+        self.input_player = Some(InputPlayer::new(recording));
+    }
 
-        Self {
-            world,
-            dispatcher: dispatcher_builder.build(),
-        }
+    pub fn is_replaying(&self) -> bool {
+        self.input_player.is_some()
     }
 
     fn update_delta_time(&mut self, ctx: &mut Context) {
@@ -57,12 +127,84 @@ impl<'a, 'b> Planet<'a, 'b> {
 
     fn update_mouse_position(&mut self, ctx: &mut Context) {
         let mut mouse_position = self.world.write_resource::<MousePosition>();
-        mouse_position.0 = mouse::position(ctx).into();
+        mouse_position.0 = self.window_to_screen(mouse::position(ctx).into());
     }
 
-    fn update_screen_size(&mut self, width: f32, height: f32) {
-        let mut screen_size = self.world.write_resource::<ScreenSize>();
-        screen_size.0 = Vector2::new(width, height);
+    /// Converts a point in window pixels to the coordinates everything is laid out in
+    fn window_to_screen(&self, point: Point2<f32>) -> Point2<f32> {
+        self.world
+            .read_resource::<Viewport>()
+            .window_to_screen(point)
+    }
+
+    fn update_viewport(
+        &mut self,
+        ctx: &mut Context,
+        render_settings: &RenderSettings,
+    ) -> GameResult {
+        // Nothing sensible to lay out until we know how big the window is
+        if self.window_size.x <= 0.0 || self.window_size.y <= 0.0 {
+            return Ok(());
+        }
+
+        let viewport = Viewport::new(self.window_size, render_settings);
+
+        ggez_graphics::set_screen_coordinates(ctx, viewport.screen_coordinates)?;
+
+        self.world.write_resource::<ScreenSize>().0 = viewport.screen_size();
+        *self.world.write_resource::<Viewport>() = viewport;
+
+        Ok(())
+    }
+
+    fn limit_frame_rate(&mut self, frame_cap: Option<u32>) {
+        if let Some(frame_cap) = frame_cap {
+            let frame_time = Duration::from_float_secs(1.0 / f64::from(frame_cap));
+            let elapsed = self.last_frame.elapsed();
+
+            if elapsed < frame_time {
+                timer::sleep(frame_time - elapsed);
+            }
+        }
+
+        self.last_frame = Instant::now();
+    }
+
+    fn apply_cursor_settings(&mut self, ctx: &mut Context) -> GameResult {
+        let cursor_settings = *self.world.read_resource::<CursorSettings>();
+
+        if self.cursor_settings != Some(cursor_settings) {
+            cursor_settings.apply(ctx)?;
+            self.cursor_settings = Some(cursor_settings);
+        }
+
+        Ok(())
+    }
+
+    /// Swaps the input resources out for empty ones while the debug console is open, so using
+    /// it doesn't also move the player or click focused widgets. Returns the real ones to put back
+    fn hide_input_behind_console(&mut self) -> Option<HiddenInput> {
+        let console_open = self.world.res.has_value::<DebugConsole>()
+            && self.world.read_resource::<DebugConsole>().open;
+
+        if console_open {
+            Some(HiddenInput {
+                keys: take_resource(&self.world),
+                gamepad_buttons: take_resource(&self.world),
+                mouse_buttons: take_resource(&self.world),
+                mouse_motion: take_resource(&self.world),
+                mouse_wheel: take_resource(&self.world),
+            })
+        } else {
+            None
+        }
+    }
+
+    fn measure_text(&mut self, ctx: &mut Context) {
+        let fonts = self.world.read_resource::<Fonts>();
+        let mut text_metrics = self.world.write_resource::<TextMetrics>();
+
+        text_metrics.measure_pending(ctx, &fonts.0);
     }
 
     fn update_keys(&mut self) {
@@ -70,6 +212,11 @@ impl<'a, 'b> Planet<'a, 'b> {
         keys.update();
     }
 
+    fn update_gamepad_buttons(&mut self) {
+        let mut gamepad_buttons = self.world.write_resource::<GamepadButtons>();
+        gamepad_buttons.update();
+    }
+
     fn update_mouse_buttons(&mut self) {
         let mut mouse_buttons = self.world.write_resource::<MouseButtons>();
         mouse_buttons.update();
@@ -95,79 +242,406 @@ impl<'a, 'b> Planet<'a, 'b> {
         });
     }
 
-    fn render(&mut self, ctx: &mut Context) {
-        let mut rendering_system = RenderingSystem::new(ctx);
+    fn update_touches(&mut self) {
+        let mut touches = self.world.write_resource::<Touches>();
+        touches.update();
+    }
+
+    /// ggez doesn't hand touches to event handlers, so anything running its own event loop
+    /// can forward them here. Synthetic touches can be injected the same way
+    pub fn touch_event(&mut self, ctx: &mut Context, id: u64, phase: TouchPhase, x: f32, y: f32) {
+        let position = self.window_to_screen(Point2::new(x, y));
+
+        self.publish_input_event(
+            ctx,
+            InputEvent::Touch {
+                id,
+                phase,
+                position,
+            },
+        );
+    }
+
+    fn record_or_replay_input(&mut self) {
+        if let Some(input_player) = &mut self.input_player {
+            if !input_player.play_frame(&mut self.world.res) {
+                self.input_player = None;
+            }
+        }
+
+        if let Some(input_recording) = &mut self.input_recording {
+            input_recording
+                .frames
+                .push(InputFrame::capture(&self.world.res));
+        }
+    }
+
+    fn run_fixed_steps(&mut self) {
+        if let Some(fixed_stepper) = &mut self.fixed_stepper {
+            let delta_time = self.world.read_resource::<DeltaTime>().0;
+
+            fixed_stepper.run(&mut self.world, delta_time);
+        }
+    }
+
+    fn publish_input_event(&mut self, ctx: &mut Context, event: InputEvent) {
+        self.receive_input_event(timer::time_since_start(ctx), event);
+    }
+
+    /// Feeds a live input event into the input resources and `InputEvents`.
+    /// While a recording is being replayed it stands in for live input, so that's dropped
+    fn receive_input_event(&mut self, time: Duration, event: InputEvent) {
+        let is_player_input = match event {
+            InputEvent::Resize(_) => false,
+            _ => true,
+        };
+
+        if is_player_input && self.is_replaying() {
+            return;
+        }
+
+        match event {
+            InputEvent::KeyDown { keycode, .. } => {
+                self.world.write_resource::<Keys>().press(keycode);
+            }
+            InputEvent::KeyUp { keycode, .. } => {
+                self.world.write_resource::<Keys>().release(keycode);
+            }
+            InputEvent::MouseButtonDown { button, .. } => {
+                self.world.write_resource::<MouseButtons>().press(button);
+            }
+            InputEvent::MouseButtonUp { button, .. } => {
+                self.world.write_resource::<MouseButtons>().release(button);
+            }
+            InputEvent::MouseMotion { delta, .. } => self.update_mouse_motion(Some(delta)),
+            InputEvent::MouseWheel(wheel) => self.update_mouse_wheel(Some(wheel)),
+            InputEvent::Touch {
+                id,
+                phase,
+                position,
+            } => {
+                self.world
+                    .write_resource::<Touches>()
+                    .touch(id, phase, position);
+            }
+            InputEvent::GamepadButtonDown(button) => {
+                self.world.write_resource::<GamepadButtons>().press(button);
+            }
+            InputEvent::GamepadButtonUp(button) => {
+                self.world
+                    .write_resource::<GamepadButtons>()
+                    .release(button);
+            }
+            InputEvent::TextInput(_) | InputEvent::Resize(_) => {}
+        }
+
+        let mut input_events = self.world.write_resource::<InputEvents>();
+        input_events.single_write(TimedInputEvent { time, event });
+    }
+
+    fn publish_window_event(&mut self, event: WindowEvent) {
+        let mut window_events = self.world.write_resource::<WindowEvents>();
+        window_events.single_write(event);
+    }
+
+    fn render(&mut self, ctx: &mut Context) -> GameResult {
+        UiLayout.run_now(&self.world.res);
+
+        let mut rendering_system = RenderingSystem::new(ctx, &mut self.clip_canvas);
         rendering_system.run_now(&self.world.res);
+        rendering_system.finish()
     }
 }
 
 impl<'a, 'b> event::EventHandler for Planet<'a, 'b> {
     fn update(&mut self, ctx: &mut Context) -> GameResult {
+        let render_settings = self.world.read_resource::<RenderSettings>().clone();
+
+        self.update_viewport(ctx, &render_settings)?;
         self.update_delta_time(ctx);
         self.update_mouse_position(ctx);
+        self.record_or_replay_input();
 
+        let hidden_input = self.hide_input_behind_console();
+        self.run_fixed_steps();
         self.dispatcher.dispatch(&self.world.res);
+        self.states.dispatch(&mut self.world);
+        self.states.apply_transition(&mut self.world);
+
+        if let Some(hidden_input) = hidden_input {
+            hidden_input.restore(&self.world);
+        }
+
+        commands::run_commands(ctx, &mut self.world);
+
+        if self.world.res.has_value::<DebugConsole>() {
+            console::run_console_commands(&mut self.world);
+        }
+
+        // Entities deleted and components added lazily by systems, like the title bars
+        // of closed windows, only go away or show up once the world is maintained
+        self.world.maintain();
+
+        self.apply_cursor_settings(ctx)?;
+        self.measure_text(ctx);
 
         self.update_keys();
         self.update_mouse_buttons();
+        self.update_gamepad_buttons();
+        self.update_touches();
         self.update_mouse_motion(None);
         self.update_mouse_wheel(None);
 
+        if self.world.read_resource::<QuitRequest>().0 {
+            event::quit(ctx);
+        }
+
         Ok(())
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        ggez_graphics::clear(ctx, [0.1, 0.2, 0.3, 1.0].into());
+        let render_settings = self.world.read_resource::<RenderSettings>().clone();
 
-        self.render(ctx);
+        self.update_viewport(ctx, &render_settings)?;
+        ggez_graphics::set_default_filter(ctx, render_settings.filter_mode);
+
+        if let Some(clear_color) = render_settings.clear_color {
+            ggez_graphics::clear(ctx, clear_color);
+        }
+
+        self.render(ctx)?;
+        draw_letterbox(
+            ctx,
+            &render_settings,
+            &self.world.read_resource::<Viewport>(),
+        )?;
 
         ggez_graphics::present(ctx)?;
+        self.limit_frame_rate(render_settings.frame_cap);
+
         Ok(())
     }
 
     fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) {
-        let _ = ggez_graphics::set_screen_coordinates(ctx, Rect::new(0.0, 0.0, width, height));
+        let minimized = width <= 0.0 || height <= 0.0;
+        let was_minimized = self.world.read_resource::<WindowState>().minimized;
+
+        if minimized != was_minimized {
+            self.world.write_resource::<WindowState>().minimized = minimized;
+            self.publish_window_event(if minimized {
+                WindowEvent::Minimized
+            } else {
+                WindowEvent::Restored
+            });
+        }
+
+        // Keep laying things out for the last real size while minimized
+        if !minimized {
+            self.window_size = Vector2::new(width, height);
+            // Sized for the old window
+            self.clip_canvas = None;
+        }
 
-        self.update_screen_size(width, height);
+        self.publish_window_event(WindowEvent::Resized(Vector2::new(width, height)));
+        self.publish_input_event(ctx, InputEvent::Resize(Vector2::new(width, height)));
     }
 
-    fn mouse_button_down_event(
-        &mut self,
-        _ctx: &mut Context,
-        button: MouseButton,
-        _x: f32,
-        _y: f32,
-    ) {
-        let mut mouse_buttons = self.world.write_resource::<MouseButtons>();
-        mouse_buttons.0.insert(button, InputState::Pressed);
+    fn focus_event(&mut self, _ctx: &mut Context, gained: bool) {
+        self.world.write_resource::<WindowState>().focused = gained;
+        self.publish_window_event(if gained {
+            WindowEvent::FocusGained
+        } else {
+            WindowEvent::FocusLost
+        });
     }
 
-    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, _x: f32, _y: f32) {
-        let mut mouse_buttons = self.world.write_resource::<MouseButtons>();
-        mouse_buttons.0.insert(button, InputState::Released);
+    fn quit_event(&mut self, _ctx: &mut Context) -> bool {
+        self.publish_window_event(WindowEvent::QuitRequested);
+
+        // Returning true cancels quitting
+        self.world.read_resource::<PreventQuit>().0
+    }
+
+    fn mouse_button_down_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        let position = self.window_to_screen(Point2::new(x, y));
+
+        self.publish_input_event(ctx, InputEvent::MouseButtonDown { button, position });
+    }
+
+    fn mouse_button_up_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        let position = self.window_to_screen(Point2::new(x, y));
+
+        self.publish_input_event(ctx, InputEvent::MouseButtonUp { button, position });
     }
 
     fn key_down_event(
         &mut self,
-        _ctx: &mut Context,
+        ctx: &mut Context,
         keycode: KeyCode,
-        _keymods: KeyMods,
-        _repeat: bool,
+        keymods: KeyMods,
+        repeat: bool,
     ) {
-        let mut keys = self.world.write_resource::<Keys>();
-        keys.0.insert(keycode, InputState::Pressed);
+        self.publish_input_event(
+            ctx,
+            InputEvent::KeyDown {
+                keycode,
+                keymods,
+                repeat,
+            },
+        );
     }
 
-    fn key_up_event(&mut self, _ctx: &mut Context, keycode: KeyCode, _keymods: KeyMods) {
-        let mut keys = self.world.write_resource::<Keys>();
-        keys.0.insert(keycode, InputState::Released);
+    fn key_up_event(&mut self, ctx: &mut Context, keycode: KeyCode, keymods: KeyMods) {
+        self.publish_input_event(ctx, InputEvent::KeyUp { keycode, keymods });
+    }
+
+    fn controller_button_down_event(&mut self, ctx: &mut Context, button: Button, _id: usize) {
+        self.publish_input_event(ctx, InputEvent::GamepadButtonDown(button));
+    }
+
+    fn controller_button_up_event(&mut self, ctx: &mut Context, button: Button, _id: usize) {
+        self.publish_input_event(ctx, InputEvent::GamepadButtonUp(button));
+    }
+
+    fn text_input_event(&mut self, ctx: &mut Context, character: char) {
+        self.publish_input_event(ctx, InputEvent::TextInput(character));
+    }
+
+    fn mouse_motion_event(&mut self, ctx: &mut Context, x: f32, y: f32, dx: f32, dy: f32) {
+        let position = self.window_to_screen(Point2::new(x, y));
+        let delta = self
+            .world
+            .read_resource::<Viewport>()
+            .window_to_screen_delta(Vector2::new(dx, dy));
+
+        self.publish_input_event(ctx, InputEvent::MouseMotion { position, delta });
+    }
+
+    fn mouse_wheel_event(&mut self, ctx: &mut Context, x: f32, y: f32) {
+        self.publish_input_event(ctx, InputEvent::MouseWheel(Vector2::new(x, y)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A planet with just the input resources, since building one needs a window
+    fn planet<'a, 'b>() -> Planet<'a, 'b> {
+        let mut world = World::new();
+        world.add_resource(Keys::default());
+        world.add_resource(MouseButtons::default());
+        world.add_resource(GamepadButtons::default());
+        world.add_resource(MouseMotion::default());
+        world.add_resource(MouseWheel::default());
+        world.add_resource(Touches::default());
+        world.add_resource(InputEvents::new());
+
+        Planet {
+            world,
+            dispatcher: DispatcherBuilder::new().build(),
+            input_recording: None,
+            input_player: None,
+            fixed_stepper: None,
+            states: StateMachine::default(),
+            window_size: Vector2::zeros(),
+            last_frame: Instant::now(),
+            cursor_settings: None,
+            clip_canvas: None,
+        }
+    }
+
+    fn key_down(keycode: KeyCode) -> InputEvent {
+        InputEvent::KeyDown {
+            keycode,
+            keymods: KeyMods::NONE,
+            repeat: false,
+        }
     }
 
-    fn mouse_motion_event(&mut self, _ctx: &mut Context, _x: f32, _y: f32, dx: f32, dy: f32) {
-        self.update_mouse_motion(Some(Vector2::new(dx, dy)));
+    #[test]
+    fn input_is_hidden_while_the_console_is_open() {
+        let mut planet = planet();
+        planet.world.add_resource(DebugConsole::default());
+        planet.world.write_resource::<Keys>().press(KeyCode::Return);
+        planet
+            .world
+            .write_resource::<GamepadButtons>()
+            .press(Button::South);
+
+        assert!(planet.hide_input_behind_console().is_none());
+
+        planet.world.write_resource::<DebugConsole>().open = true;
+        let hidden_input = planet.hide_input_behind_console().unwrap();
+
+        assert!(!planet
+            .world
+            .read_resource::<Keys>()
+            .is_pressed(&KeyCode::Return));
+        assert!(!planet
+            .world
+            .read_resource::<GamepadButtons>()
+            .is_pressed(&Button::South));
+
+        hidden_input.restore(&planet.world);
+
+        assert!(planet
+            .world
+            .read_resource::<Keys>()
+            .is_pressed(&KeyCode::Return));
+        assert!(planet
+            .world
+            .read_resource::<GamepadButtons>()
+            .is_pressed(&Button::South));
     }
 
-    fn mouse_wheel_event(&mut self, _ctx: &mut Context, x: f32, y: f32) {
-        self.update_mouse_wheel(Some(Vector2::new(x, y)));
+    #[test]
+    fn live_input_is_ignored_while_replaying() {
+        let mut planet = planet();
+        let mut reader = planet
+            .world
+            .write_resource::<InputEvents>()
+            .register_reader();
+
+        planet.replay(InputRecording::default());
+        planet.receive_input_event(Duration::from_secs(1), key_down(KeyCode::Space));
+        planet.receive_input_event(
+            Duration::from_secs(1),
+            InputEvent::Touch {
+                id: 0,
+                phase: TouchPhase::Started,
+                position: Point2::new(1.0, 1.0),
+            },
+        );
+
+        assert!(!planet
+            .world
+            .read_resource::<Keys>()
+            .is_down(&KeyCode::Space));
+        assert!(planet.world.read_resource::<Touches>().is_empty());
+        assert_eq!(
+            planet
+                .world
+                .read_resource::<InputEvents>()
+                .read(&mut reader)
+                .count(),
+            0
+        );
+
+        planet.input_player = None;
+        planet.receive_input_event(Duration::from_secs(2), key_down(KeyCode::Space));
+
+        assert!(planet
+            .world
+            .read_resource::<Keys>()
+            .is_down(&KeyCode::Space));
+        assert_eq!(
+            planet
+                .world
+                .read_resource::<InputEvents>()
+                .read(&mut reader)
+                .count(),
+            1
+        );
     }
 }
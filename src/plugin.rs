@@ -0,0 +1,183 @@
+use crate::{
+    commands,
+    debug::DebugPlugin,
+    graphics::{
+        self,
+        settings::{RenderSettings, Viewport},
+        ScreenSize,
+    },
+    input,
+    renderers::RenderersPlugin,
+    state::{self, State, StateMachine},
+    timestep::{self, FixedStepper, FixedTimestep},
+    widgets::WidgetsPlugin,
+    window, DeltaTime, Planet,
+};
+use ggez::{graphics as ggez_graphics, nalgebra::Vector2, Context, GameError, GameResult};
+use specs::prelude::*;
+use std::time::Instant;
+
+/// A bundle of components, resources, systems and entities that gets added to a `Planet`.
+/// Plugins are built in the order they were added, after everything `Planet` needs itself,
+/// so a plugin can name systems from earlier plugins as dependencies of its own systems
+pub trait Plugin<'a, 'b> {
+    fn build(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+    ) -> GameResult;
+}
+
+pub struct PlanetBuilder<'a, 'b> {
+    world: World,
+    dispatcher_builder: DispatcherBuilder<'a, 'b>,
+    plugins: Vec<Box<dyn Plugin<'a, 'b>>>,
+    states: Vec<(&'static str, State<'a, 'b>)>,
+    initial_state: Option<&'static str>,
+    fixed_dispatcher_builder: Option<DispatcherBuilder<'a, 'b>>,
+    fixed_timestep: Option<FixedTimestep>,
+}
+
+impl<'a, 'b> Default for PlanetBuilder<'a, 'b> {
+    fn default() -> Self {
+        Self::new(World::new(), DispatcherBuilder::new())
+    }
+}
+
+impl<'a, 'b> PlanetBuilder<'a, 'b> {
+    pub fn new(world: World, dispatcher_builder: DispatcherBuilder<'a, 'b>) -> Self {
+        Self {
+            world,
+            dispatcher_builder,
+            plugins: Vec::new(),
+            states: Vec::new(),
+            initial_state: None,
+            fixed_dispatcher_builder: None,
+            fixed_timestep: None,
+        }
+    }
+
+    pub fn with_plugin<P: Plugin<'a, 'b> + 'static>(mut self, plugin: P) -> Self {
+        self.plugins.push(Box::new(plugin));
+        self
+    }
+
+    /// Adds the built in renderers, the widgets and the debug overlay
+    pub fn with_default_plugins(self) -> Self {
+        self.with_plugin(RenderersPlugin)
+            .with_plugin(WidgetsPlugin)
+            .with_plugin(DebugPlugin)
+    }
+
+    /// Adds a state that can be transitioned to by name
+    pub fn with_state(mut self, name: &'static str, state: State<'a, 'b>) -> Self {
+        self.states.push((name, state));
+        self
+    }
+
+    /// The state pushed onto the stack when the planet is built,
+    /// building fails if no state was added with this name
+    pub fn with_initial_state(mut self, name: &'static str) -> Self {
+        self.initial_state = Some(name);
+        self
+    }
+
+    /// Adds a system that runs at a fixed rate, separately from the main dispatcher
+    /// which runs once per frame. Physics and gameplay that need to be deterministic belong here
+    pub fn with_fixed_system<S>(mut self, system: S, name: &str, dependencies: &[&str]) -> Self
+    where
+        S: for<'c> System<'c> + Send + 'a,
+    {
+        self.fixed_dispatcher_builder
+            .get_or_insert_with(DispatcherBuilder::new)
+            .add(system, name, dependencies);
+        self
+    }
+
+    /// How often the fixed systems run, 60 times a second if not set
+    pub fn with_fixed_timestep(mut self, fixed_timestep: FixedTimestep) -> Self {
+        self.fixed_timestep = Some(fixed_timestep);
+        self
+    }
+
+    pub fn build(self, ctx: &mut Context) -> GameResult<Planet<'a, 'b>> {
+        let PlanetBuilder {
+            mut world,
+            mut dispatcher_builder,
+            plugins,
+            states,
+            initial_state,
+            fixed_dispatcher_builder,
+            fixed_timestep,
+        } = self;
+
+        world.add_resource(DeltaTime::default());
+
+        // Planet reads and writes these itself every frame, so they aren't optional
+        graphics::setup(ctx, &mut world, &mut dispatcher_builder)?;
+        input::setup(ctx, &mut world, &mut dispatcher_builder);
+        timestep::setup(ctx, &mut world, &mut dispatcher_builder);
+
+        if let Some(fixed_timestep) = fixed_timestep {
+            world.add_resource(fixed_timestep);
+        }
+        state::setup(ctx, &mut world, &mut dispatcher_builder);
+        window::setup(ctx, &mut world, &mut dispatcher_builder);
+        commands::setup(ctx, &mut world, &mut dispatcher_builder);
+
+        // Lay things out for the window right away instead of waiting for it to be resized
+        let (window_width, window_height) = ggez_graphics::drawable_size(ctx);
+        let window_size = Vector2::new(window_width as f32, window_height as f32);
+        let viewport = Viewport::new(window_size, &world.read_resource::<RenderSettings>());
+
+        world.write_resource::<ScreenSize>().0 = viewport.screen_size();
+        world.add_resource(viewport);
+
+        for mut plugin in plugins {
+            plugin.build(ctx, &mut world, &mut dispatcher_builder)?;
+        }
+
+        let mut state_machine = StateMachine::default();
+
+        for (name, state) in states {
+            state_machine.add(name, state, &mut world);
+        }
+
+        if let Some(initial_state) = initial_state {
+            if !state_machine.has_state(initial_state) {
+                return Err(GameError::ResourceLoadError(format!(
+                    "There is no state named {:?} to start in",
+                    initial_state
+                )));
+            }
+
+            state_machine.push(initial_state, &mut world);
+        }
+
+        // Like the state and fixed dispatchers, so systems get to register event readers
+        // and any resources they read that nothing added yet
+        let mut dispatcher = dispatcher_builder.build();
+        dispatcher.setup(&mut world.res);
+
+        let fixed_stepper = fixed_dispatcher_builder.map(|fixed_dispatcher_builder| {
+            let mut fixed_dispatcher = fixed_dispatcher_builder.build();
+            fixed_dispatcher.setup(&mut world.res);
+
+            FixedStepper::new(fixed_dispatcher)
+        });
+
+        Ok(Planet {
+            world,
+            dispatcher,
+            input_recording: None,
+            input_player: None,
+            fixed_stepper,
+            states: state_machine,
+            window_size,
+            last_frame: Instant::now(),
+            cursor_settings: None,
+            clip_canvas: None,
+        })
+    }
+}
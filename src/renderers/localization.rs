@@ -0,0 +1,159 @@
+use ggez::{filesystem, Context, GameResult};
+use specs::prelude::*;
+use std::{collections::HashMap, io::Read};
+
+/// String tables for every locale, looked up by `LocalizedText`
+#[derive(Default)]
+pub struct Localization {
+    locale: String,
+    fallback_locale: Option<String>,
+    tables: HashMap<String, HashMap<String, String>>,
+}
+
+impl Localization {
+    /// Adds the entries of a `key = value` string table to `locale`, one entry a line.
+    /// Blank lines and lines starting with `#` are skipped
+    pub fn add_table(&mut self, locale: &str, source: &str) {
+        let table = self.tables.entry(locale.to_owned()).or_default();
+
+        for line in source.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(separator) = line.find('=') {
+                table.insert(
+                    line[..separator].trim().to_owned(),
+                    line[separator + 1..].trim().replace("\\n", "\n"),
+                );
+            }
+        }
+    }
+
+    /// Loads a string table from ggez's resource folders, see `add_table`
+    pub fn load(&mut self, ctx: &mut Context, locale: &str, path: &str) -> GameResult {
+        let mut source = String::new();
+        filesystem::open(ctx, path)?.read_to_string(&mut source)?;
+
+        self.add_table(locale, &source);
+
+        Ok(())
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Every `LocalizedText` picks up the new locale the next time it's rendered
+    pub fn set_locale(&mut self, locale: &str) {
+        self.locale = locale.to_owned();
+    }
+
+    /// Where keys missing from the current locale are looked up next,
+    /// usually the locale the game was written in
+    pub fn set_fallback_locale(&mut self, locale: &str) {
+        self.fallback_locale = Some(locale.to_owned());
+    }
+
+    /// Looks `key` up in the current locale, then the fallback locale, and fills in `{name}`
+    /// with the arg of the same name. Keys that aren't in either table come back as they are
+    /// so they're easy to spot
+    pub fn get(&self, key: &str, args: &[(String, String)]) -> String {
+        let lookup = |locale: &str| self.tables.get(locale).and_then(|table| table.get(key));
+
+        let mut text = lookup(&self.locale)
+            .or_else(|| {
+                self.fallback_locale
+                    .as_ref()
+                    .and_then(|locale| lookup(locale))
+            })
+            .cloned()
+            .unwrap_or_else(|| key.to_owned());
+
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name), value);
+        }
+
+        text
+    }
+}
+
+/// Replaces the text of the entity's `Text` with `key` looked up in `Localization`
+#[derive(Default)]
+pub struct LocalizedText {
+    pub key: String,
+    pub args: Vec<(String, String)>,
+}
+
+impl Component for LocalizedText {
+    type Storage = DenseVecStorage<Self>;
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    _dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.add_resource(Localization::default());
+    world.register::<LocalizedText>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn localization() -> Localization {
+        let mut localization = Localization::default();
+        localization.add_table(
+            "en",
+            "# Menu\n\nplay = Play\ngreeting = Hello, {name}!\nquit = Quit",
+        );
+        localization.add_table("fr", "play = Jouer\ngreeting = Bonjour, {name} !");
+        localization.set_fallback_locale("en");
+        localization.set_locale("fr");
+        localization
+    }
+
+    #[test]
+    fn looks_up_the_current_locale() {
+        let mut localization = localization();
+
+        assert_eq!(localization.get("play", &[]), "Jouer");
+
+        localization.set_locale("en");
+
+        assert_eq!(localization.get("play", &[]), "Play");
+    }
+
+    #[test]
+    fn fills_in_args() {
+        let localization = localization();
+        let args = vec![("name".to_owned(), "Ada".to_owned())];
+
+        assert_eq!(localization.get("greeting", &args), "Bonjour, Ada !");
+    }
+
+    #[test]
+    fn falls_back_to_the_fallback_locale() {
+        let mut localization = localization();
+
+        assert_eq!(localization.get("quit", &[]), "Quit");
+
+        localization.set_locale("de");
+
+        assert_eq!(localization.get("play", &[]), "Play");
+    }
+
+    #[test]
+    fn missing_keys_come_back_as_they_are() {
+        let mut localization = localization();
+
+        assert_eq!(localization.get("options", &[]), "options");
+        // Comments aren't entries
+        assert_eq!(localization.get("# Menu", &[]), "# Menu");
+
+        localization.fallback_locale = None;
+
+        assert_eq!(localization.get("quit", &[]), "quit");
+    }
+}
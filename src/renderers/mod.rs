@@ -1,7 +1,10 @@
 pub mod column_graph;
+pub mod localization;
+pub mod panel;
 pub mod text;
 
-use ggez::Context;
+use crate::plugin::Plugin;
+use ggez::{Context, GameResult};
 use specs::prelude::*;
 
 pub fn setup<'a, 'b>(
@@ -10,5 +13,23 @@ pub fn setup<'a, 'b>(
     dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
 ) {
     column_graph::setup(ctx, world, dispatcher_builder);
+    localization::setup(ctx, world, dispatcher_builder);
+    panel::setup(ctx, world, dispatcher_builder);
     text::setup(ctx, world, dispatcher_builder);
 }
+
+/// The text, column graph and panel renderers
+pub struct RenderersPlugin;
+
+impl<'a, 'b> Plugin<'a, 'b> for RenderersPlugin {
+    fn build(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+    ) -> GameResult {
+        setup(ctx, world, dispatcher_builder);
+
+        Ok(())
+    }
+}
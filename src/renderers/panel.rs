@@ -0,0 +1,165 @@
+use crate::graphics::{
+    rendering::{ImageBuilder, RenderInstruction, Renderable},
+    ui::layout::{Edges, UiRect},
+    Images,
+};
+use ggez::{
+    graphics::{Color, DrawMode, DrawParam, Image, MeshBuilder, Rect},
+    nalgebra::{Point2, Vector2},
+    Context,
+};
+use specs::prelude::*;
+
+/// A background filling the element's `UiRect`. Children need a higher `Layer`,
+/// or to be created after the panel, to be drawn on top of it
+pub enum Panel {
+    Solid {
+        color: Color,
+        border: Option<(f32, Color)>,
+    },
+    /// Splits an image loaded into `Images` into a three by three grid,
+    /// `slices` being how far in from each edge of the image the cuts are.
+    /// The corners keep their size while the edges and the center stretch
+    NineSlice { image: String, slices: Edges },
+}
+
+impl Component for Panel {
+    type Storage = VecStorage<Self>;
+}
+
+fn solid(color: Color, border: Option<(f32, Color)>, w: f32, h: f32) -> RenderInstruction {
+    let mut mesh_builder = MeshBuilder::new();
+    let mut fill = Rect::new(0.0, 0.0, w, h);
+
+    if let Some((width, border_color)) = border {
+        mesh_builder.rectangle(DrawMode::fill(), fill, border_color);
+        fill = Edges::uniform(width).shrink(fill);
+    }
+
+    if fill.w > 0.0 && fill.h > 0.0 {
+        mesh_builder.rectangle(DrawMode::fill(), fill, color);
+    }
+
+    RenderInstruction::Mesh(mesh_builder)
+}
+
+fn nine_slice(image: &Image, slices: Edges, w: f32, h: f32) -> Vec<DrawParam> {
+    let image_w = f32::from(image.width());
+    let image_h = f32::from(image.height());
+
+    // Where each column and row starts and how big it is, in the image then on screen
+    let columns = [
+        (0.0, slices.left, 0.0, slices.left),
+        (
+            slices.left,
+            image_w - slices.horizontal(),
+            slices.left,
+            w - slices.horizontal(),
+        ),
+        (
+            image_w - slices.right,
+            slices.right,
+            w - slices.right,
+            slices.right,
+        ),
+    ];
+    let rows = [
+        (0.0, slices.top, 0.0, slices.top),
+        (
+            slices.top,
+            image_h - slices.vertical(),
+            slices.top,
+            h - slices.vertical(),
+        ),
+        (
+            image_h - slices.bottom,
+            slices.bottom,
+            h - slices.bottom,
+            slices.bottom,
+        ),
+    ];
+
+    let mut sprites = Vec::with_capacity(9);
+
+    for (src_x, src_w, dest_x, dest_w) in columns.iter() {
+        for (src_y, src_h, dest_y, dest_h) in rows.iter() {
+            // Edges collapse when the panel is smaller than the slices
+            if *src_w <= 0.0 || *src_h <= 0.0 || *dest_w <= 0.0 || *dest_h <= 0.0 {
+                continue;
+            }
+
+            sprites.push(
+                DrawParam::default()
+                    .src(Rect::new(
+                        src_x / image_w,
+                        src_y / image_h,
+                        src_w / image_w,
+                        src_h / image_h,
+                    ))
+                    .dest(Point2::new(*dest_x, *dest_y))
+                    .scale(Vector2::new(dest_w / src_w, dest_h / src_h)),
+            );
+        }
+    }
+
+    sprites
+}
+
+#[derive(Default)]
+pub struct PanelRenderer;
+
+impl<'a> System<'a> for PanelRenderer {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Images>,
+        WriteStorage<'a, Renderable>,
+        ReadStorage<'a, Panel>,
+        ReadStorage<'a, UiRect>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, images, mut renderables, panels, ui_rects) = data;
+
+        for (entity, panel, ui_rect) in (&*entities, &panels, &ui_rects).join() {
+            let (w, h) = (ui_rect.0.w, ui_rect.0.h);
+
+            if w <= 0.0 || h <= 0.0 {
+                renderables.remove(entity);
+                continue;
+            }
+
+            let instruction = match panel {
+                Panel::Solid { color, border } => solid(*color, *border, w, h),
+                Panel::NineSlice { image, slices } => {
+                    if let Some(loaded) = images.0.get(image) {
+                        RenderInstruction::SpriteBatch {
+                            image_builder: ImageBuilder::Loaded(image.clone()),
+                            sprites: nine_slice(loaded, *slices, w, h),
+                        }
+                    } else {
+                        // Not loaded yet
+                        continue;
+                    }
+                }
+            };
+
+            let _ = renderables.insert(
+                entity,
+                Renderable {
+                    instruction,
+                    draw_param: None,
+                },
+            );
+        }
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.register::<Panel>();
+
+    dispatcher_builder.add(PanelRenderer::default(), "panel_renderer", &[]);
+}
@@ -1,12 +1,23 @@
-use crate::graphics::rendering::{RenderInstruction, Renderable};
+use super::localization::{Localization, LocalizedText};
+use crate::graphics::{
+    rendering::{RenderInstruction, Renderable},
+    text::Overflow,
+};
 use ggez::{graphics::Scale, Context};
+use smart_default::SmartDefault;
 use specs::prelude::*;
 
 // TODO: Implement color text
+#[derive(SmartDefault)]
 pub struct Text {
     pub text: String,
+    #[default = "roboto"]
     pub font: &'static str,
+    #[default(Scale::uniform(16.0))]
     pub scale: Scale,
+    /// Text wider than this overflows, see `Overflow`
+    pub max_width: Option<f32>,
+    pub overflow: Overflow,
 }
 
 impl Component for Text {
@@ -19,12 +30,18 @@ pub struct TextRenderer;
 impl<'a> System<'a> for TextRenderer {
     type SystemData = (
         Entities<'a>,
+        Read<'a, Localization>,
         WriteStorage<'a, Renderable>,
-        ReadStorage<'a, Text>,
+        WriteStorage<'a, Text>,
+        ReadStorage<'a, LocalizedText>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (entities, mut renderables, texts) = data;
+        let (entities, localization, mut renderables, mut texts, localized_texts) = data;
+
+        for (text, localized_text) in (&mut texts, &localized_texts).join() {
+            text.text = localization.get(&localized_text.key, &localized_text.args);
+        }
 
         for (entity, text) in (&*entities, &texts).join() {
             let _ = renderables.insert(
@@ -34,6 +51,8 @@ impl<'a> System<'a> for TextRenderer {
                         text: text.text.clone(),
                         font: text.font,
                         scale: text.scale,
+                        max_width: text.max_width,
+                        overflow: text.overflow,
                     },
                     draw_param: None,
                 },
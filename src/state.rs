@@ -0,0 +1,270 @@
+use ggez::Context;
+use log::error;
+use specs::prelude::*;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Transition {
+    /// Pauses the current state and starts the named one on top of it
+    Push(&'static str),
+    /// Exits the current state and resumes the one under it
+    Pop,
+    /// Exits the current state and replaces it with the named one
+    Switch(&'static str),
+}
+
+/// Systems write to this to change states,
+/// it gets applied once everything for the frame has been dispatched
+#[derive(Default)]
+pub struct StateTransition(pub Option<Transition>);
+
+/// The names of the states on the stack, bottom first.
+/// Write to `StateTransition` to change it
+#[derive(Default)]
+pub struct StateStack(pub Vec<&'static str>);
+
+/// A screen of the game, like a menu, gameplay or a pause screen.
+/// Its dispatcher only runs while it's on top of the stack
+pub struct State<'a, 'b> {
+    dispatcher: Dispatcher<'a, 'b>,
+    on_enter: Option<Box<dyn FnMut(&mut World)>>,
+    on_exit: Option<Box<dyn FnMut(&mut World)>>,
+}
+
+impl<'a, 'b> State<'a, 'b> {
+    pub fn new(dispatcher_builder: DispatcherBuilder<'a, 'b>) -> Self {
+        Self {
+            dispatcher: dispatcher_builder.build(),
+            on_enter: None,
+            on_exit: None,
+        }
+    }
+
+    /// Runs when the state is pushed or switched to,
+    /// a good place to create the entities that belong to it
+    pub fn on_enter<F: FnMut(&mut World) + 'static>(mut self, on_enter: F) -> Self {
+        self.on_enter = Some(Box::new(on_enter));
+        self
+    }
+
+    /// Runs when the state is popped or switched away from
+    pub fn on_exit<F: FnMut(&mut World) + 'static>(mut self, on_exit: F) -> Self {
+        self.on_exit = Some(Box::new(on_exit));
+        self
+    }
+
+    fn enter(&mut self, world: &mut World) {
+        if let Some(on_enter) = &mut self.on_enter {
+            on_enter(world);
+        }
+    }
+
+    fn exit(&mut self, world: &mut World) {
+        if let Some(on_exit) = &mut self.on_exit {
+            on_exit(world);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct StateMachine<'a, 'b> {
+    states: HashMap<&'static str, State<'a, 'b>>,
+    stack: Vec<&'static str>,
+}
+
+impl<'a, 'b> StateMachine<'a, 'b> {
+    pub fn add(&mut self, name: &'static str, mut state: State<'a, 'b>, world: &mut World) {
+        state.dispatcher.setup(&mut world.res);
+        self.states.insert(name, state);
+    }
+
+    pub fn has_state(&self, name: &'static str) -> bool {
+        self.states.contains_key(name)
+    }
+
+    /// Runs the dispatcher of the state on top of the stack
+    pub fn dispatch(&mut self, world: &mut World) {
+        if let Some(name) = self.stack.last() {
+            if let Some(state) = self.states.get_mut(name) {
+                state.dispatcher.dispatch(&world.res);
+            }
+        }
+    }
+
+    /// Applies the transition systems asked for this frame, if any.
+    /// Transitions to states that don't exist are logged and ignored
+    pub fn apply_transition(&mut self, world: &mut World) {
+        let transition = world.write_resource::<StateTransition>().0.take();
+
+        if let Some(Transition::Push(name)) | Some(Transition::Switch(name)) = transition {
+            if !self.has_state(name) {
+                error!("There is no state named {:?} to transition to", name);
+                return;
+            }
+        }
+
+        match transition {
+            Some(Transition::Push(name)) => self.push(name, world),
+            Some(Transition::Pop) => self.pop(world),
+            Some(Transition::Switch(name)) => {
+                self.pop(world);
+                self.push(name, world);
+            }
+            None => {}
+        }
+    }
+
+    pub fn push(&mut self, name: &'static str, world: &mut World) {
+        if !self.has_state(name) {
+            error!("There is no state named {:?} to push", name);
+            return;
+        }
+
+        self.state(name).enter(world);
+        self.stack.push(name);
+
+        self.update_state_stack(world);
+    }
+
+    pub fn pop(&mut self, world: &mut World) {
+        if let Some(name) = self.stack.pop() {
+            self.state(name).exit(world);
+        }
+
+        self.update_state_stack(world);
+    }
+
+    fn state(&mut self, name: &'static str) -> &mut State<'a, 'b> {
+        self.states
+            .get_mut(name)
+            .unwrap_or_else(|| panic!("There is no state named {:?}", name))
+    }
+
+    fn update_state_stack(&self, world: &mut World) {
+        let mut state_stack = world.write_resource::<StateStack>();
+        state_stack.0 = self.stack.clone();
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    _dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.add_resource(StateTransition::default());
+    world.add_resource(StateStack::default());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Log(Vec<String>);
+
+    struct LogRun(&'static str);
+
+    impl<'a> System<'a> for LogRun {
+        type SystemData = Write<'a, Log>;
+
+        fn run(&mut self, mut log: Self::SystemData) {
+            log.0.push(format!("run {}", self.0));
+        }
+    }
+
+    fn logged_state<'a, 'b>(name: &'static str) -> State<'a, 'b> {
+        State::new(DispatcherBuilder::new().with(LogRun(name), "log_run", &[]))
+            .on_enter(move |world| {
+                world
+                    .write_resource::<Log>()
+                    .0
+                    .push(format!("enter {}", name))
+            })
+            .on_exit(move |world| {
+                world
+                    .write_resource::<Log>()
+                    .0
+                    .push(format!("exit {}", name))
+            })
+    }
+
+    fn state_machine<'a, 'b>(world: &mut World) -> StateMachine<'a, 'b> {
+        world.add_resource(StateTransition::default());
+        world.add_resource(StateStack::default());
+        world.add_resource(Log::default());
+
+        let mut state_machine = StateMachine::default();
+        state_machine.add("menu", logged_state("menu"), world);
+        state_machine.add("game", logged_state("game"), world);
+        state_machine.push("menu", world);
+        state_machine
+    }
+
+    fn transition(state_machine: &mut StateMachine, world: &mut World, transition: Transition) {
+        world.write_resource::<StateTransition>().0 = Some(transition);
+        state_machine.apply_transition(world);
+    }
+
+    fn stack(world: &World) -> Vec<&'static str> {
+        world.read_resource::<StateStack>().0.clone()
+    }
+
+    fn log(world: &World) -> Vec<String> {
+        world.read_resource::<Log>().0.clone()
+    }
+
+    #[test]
+    fn push_and_pop() {
+        let mut world = World::new();
+        let mut state_machine = state_machine(&mut world);
+
+        transition(&mut state_machine, &mut world, Transition::Push("game"));
+        assert_eq!(stack(&world), vec!["menu", "game"]);
+
+        transition(&mut state_machine, &mut world, Transition::Pop);
+        assert_eq!(stack(&world), vec!["menu"]);
+        assert_eq!(log(&world), vec!["enter menu", "enter game", "exit game"]);
+    }
+
+    #[test]
+    fn switch_exits_before_entering() {
+        let mut world = World::new();
+        let mut state_machine = state_machine(&mut world);
+
+        transition(&mut state_machine, &mut world, Transition::Switch("game"));
+
+        assert_eq!(stack(&world), vec!["game"]);
+        assert_eq!(log(&world), vec!["enter menu", "exit menu", "enter game"]);
+    }
+
+    #[test]
+    fn only_the_top_state_is_dispatched() {
+        let mut world = World::new();
+        let mut state_machine = state_machine(&mut world);
+
+        transition(&mut state_machine, &mut world, Transition::Push("game"));
+        state_machine.dispatch(&mut world);
+
+        assert_eq!(log(&world).last().map(String::as_str), Some("run game"));
+        assert!(!log(&world).contains(&"run menu".to_owned()));
+    }
+
+    #[test]
+    fn unknown_states_are_ignored() {
+        let mut world = World::new();
+        let mut state_machine = state_machine(&mut world);
+
+        transition(
+            &mut state_machine,
+            &mut world,
+            Transition::Switch("credits"),
+        );
+        transition(&mut state_machine, &mut world, Transition::Push("credits"));
+        state_machine.push("credits", &mut world);
+
+        assert!(!state_machine.has_state("credits"));
+        assert_eq!(stack(&world), vec!["menu"]);
+        assert_eq!(log(&world), vec!["enter menu"]);
+        assert_eq!(world.read_resource::<StateTransition>().0, None);
+    }
+}
@@ -0,0 +1,235 @@
+use crate::{
+    graphics::Position,
+    input::{GamepadButtons, InputResource, Keys, MouseButtons},
+};
+use ggez::{nalgebra::Point2, Context};
+use smart_default::SmartDefault;
+use specs::prelude::*;
+use std::{hash::Hash, mem};
+
+/// How often the fixed dispatcher runs. Systems in it should
+/// use `step` as their delta time instead of `DeltaTime`.
+/// Presses and releases in `Keys`, `MouseButtons` and `GamepadButtons` are
+/// held back until the next fixed step and only seen by that one step
+#[derive(SmartDefault)]
+pub struct FixedTimestep {
+    /// Seconds between each step
+    #[default(1.0 / 60.0)]
+    pub step: f32,
+    /// The most steps run in a single frame, so one slow frame doesn't snowball
+    #[default(5)]
+    pub max_steps: u32,
+}
+
+impl FixedTimestep {
+    pub fn from_rate(rate: f32) -> Self {
+        Self {
+            step: 1.0 / rate,
+            ..Self::default()
+        }
+    }
+}
+
+/// How far the current frame is from the last fixed step to the next one, from 0 to 1
+#[derive(SmartDefault)]
+pub struct InterpolationAlpha(#[default(1.0)] pub f32);
+
+/// Where the entity was before the last fixed step, the renderer
+/// blends between this and `Position` using `InterpolationAlpha`
+pub struct PreviousPosition(pub Point2<f32>);
+
+impl Component for PreviousPosition {
+    type Storage = VecStorage<Self>;
+}
+
+pub fn interpolate(
+    position: &Position,
+    previous_position: Option<&PreviousPosition>,
+    alpha: f32,
+) -> Point2<f32> {
+    match previous_position {
+        Some(previous_position) => previous_position.0 + (position.0 - previous_position.0) * alpha,
+        None => position.0,
+    }
+}
+
+/// Input since the last fixed step. Frames can run zero or several
+/// steps, so the frame's own input would drop or repeat presses
+#[derive(Default)]
+struct LatchedInput {
+    keys: Keys,
+    mouse_buttons: MouseButtons,
+    gamepad_buttons: GamepadButtons,
+}
+
+impl LatchedInput {
+    fn latch(&mut self, world: &World) {
+        latch(world, &mut self.keys);
+        latch(world, &mut self.mouse_buttons);
+        latch(world, &mut self.gamepad_buttons);
+    }
+
+    /// Swaps the frame's input in the world with the latched input
+    fn swap(&mut self, world: &World) {
+        swap(world, &mut self.keys);
+        swap(world, &mut self.mouse_buttons);
+        swap(world, &mut self.gamepad_buttons);
+    }
+
+    /// Clears the edges once a step has seen them
+    fn consume(&mut self) {
+        self.keys.update();
+        self.mouse_buttons.update();
+        self.gamepad_buttons.update();
+    }
+}
+
+fn latch<K>(world: &World, latched: &mut InputResource<K>)
+where
+    K: Hash + Eq + Copy + Send + Sync + 'static,
+{
+    latched.latch(&world.read_resource::<InputResource<K>>());
+}
+
+fn swap<K>(world: &World, latched: &mut InputResource<K>)
+where
+    K: Hash + Eq + Copy + Send + Sync + 'static,
+{
+    mem::swap(&mut *world.write_resource::<InputResource<K>>(), latched);
+}
+
+pub struct FixedStepper<'a, 'b> {
+    dispatcher: Dispatcher<'a, 'b>,
+    accumulator: f32,
+    input: LatchedInput,
+}
+
+impl<'a, 'b> FixedStepper<'a, 'b> {
+    pub fn new(dispatcher: Dispatcher<'a, 'b>) -> Self {
+        Self {
+            dispatcher,
+            accumulator: 0.0,
+            input: LatchedInput::default(),
+        }
+    }
+
+    /// Runs as many fixed steps as `delta_time` adds up to and updates `InterpolationAlpha`
+    pub fn run(&mut self, world: &mut World, delta_time: f32) {
+        let (step, max_steps) = {
+            let fixed_timestep = world.read_resource::<FixedTimestep>();
+            (fixed_timestep.step, fixed_timestep.max_steps)
+        };
+
+        self.accumulator += delta_time;
+        self.input.latch(world);
+
+        let mut steps = 0;
+        while self.accumulator >= step && steps < max_steps {
+            store_previous_positions(world);
+
+            self.input.swap(world);
+            self.dispatcher.dispatch(&world.res);
+            self.input.swap(world);
+            self.input.consume();
+
+            self.accumulator -= step;
+            steps += 1;
+        }
+
+        // Give up on the time we couldn't catch up on instead of carrying it forever
+        if self.accumulator >= step {
+            self.accumulator %= step;
+        }
+
+        let mut interpolation_alpha = world.write_resource::<InterpolationAlpha>();
+        interpolation_alpha.0 = self.accumulator / step;
+    }
+}
+
+fn store_previous_positions(world: &World) {
+    let positions = world.read_storage::<Position>();
+    let mut previous_positions = world.write_storage::<PreviousPosition>();
+
+    for (position, previous_position) in (&positions, &mut previous_positions).join() {
+        previous_position.0 = position.0;
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    _dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.register::<PreviousPosition>();
+
+    world.add_resource(FixedTimestep::default());
+    world.add_resource(InterpolationAlpha::default());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ggez::input::keyboard::KeyCode;
+
+    #[derive(Default)]
+    struct Presses(usize);
+
+    struct CountPresses;
+
+    impl<'a> System<'a> for CountPresses {
+        type SystemData = (Read<'a, Keys>, Write<'a, Presses>);
+
+        fn run(&mut self, (keys, mut presses): Self::SystemData) {
+            if keys.is_pressed(&KeyCode::Space) {
+                presses.0 += 1;
+            }
+        }
+    }
+
+    fn stepper<'a, 'b>(world: &mut World) -> FixedStepper<'a, 'b> {
+        world.add_resource(FixedTimestep::default());
+        world.add_resource(InterpolationAlpha::default());
+        world.add_resource(Keys::default());
+        world.add_resource(MouseButtons::default());
+        world.add_resource(GamepadButtons::default());
+        world.register::<Position>();
+        world.register::<PreviousPosition>();
+
+        let mut dispatcher = DispatcherBuilder::new()
+            .with(CountPresses, "count_presses", &[])
+            .build();
+        dispatcher.setup(&mut world.res);
+
+        FixedStepper::new(dispatcher)
+    }
+
+    #[test]
+    fn press_in_a_frame_without_steps_reaches_the_next_step() {
+        let mut world = World::new();
+        let mut stepper = stepper(&mut world);
+        let step = world.read_resource::<FixedTimestep>().step;
+
+        world.write_resource::<Keys>().press(KeyCode::Space);
+        stepper.run(&mut world, step / 2.0);
+        world.write_resource::<Keys>().update();
+
+        assert_eq!(world.read_resource::<Presses>().0, 0);
+
+        stepper.run(&mut world, step / 2.0);
+
+        assert_eq!(world.read_resource::<Presses>().0, 1);
+    }
+
+    #[test]
+    fn press_in_a_frame_with_several_steps_is_seen_once() {
+        let mut world = World::new();
+        let mut stepper = stepper(&mut world);
+        let step = world.read_resource::<FixedTimestep>().step;
+
+        world.write_resource::<Keys>().press(KeyCode::Space);
+        stepper.run(&mut world, step * 3.0);
+
+        assert_eq!(world.read_resource::<Presses>().0, 1);
+        assert!(world.read_resource::<Keys>().is_pressed(&KeyCode::Space));
+    }
+}
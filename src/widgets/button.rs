@@ -0,0 +1,96 @@
+use super::{
+    focus::{Focusable, Focused},
+    laid_out_rect, mesh_renderable, rectangle, UiEvent, UiEvents, WidgetStyle,
+};
+use crate::{
+    graphics::{rendering::Renderable, ui::layout::UiRect},
+    input::picking::{Clicked, Hovered, Pickable, Pressed},
+};
+use ggez::{graphics::MeshBuilder, Context};
+use specs::prelude::*;
+
+/// Sends `UiEvent::Clicked` when clicked, put a `Text` inside it with `UiParent` for a label
+#[derive(Default)]
+pub struct Button;
+
+impl Component for Button {
+    type Storage = NullStorage<Self>;
+}
+
+pub struct ButtonSystem;
+
+impl<'a> System<'a> for ButtonSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, WidgetStyle>,
+        Write<'a, UiEvents>,
+        ReadStorage<'a, Button>,
+        ReadStorage<'a, UiRect>,
+        ReadStorage<'a, Hovered>,
+        ReadStorage<'a, Focused>,
+        ReadStorage<'a, Pressed>,
+        ReadStorage<'a, Clicked>,
+        WriteStorage<'a, Pickable>,
+        WriteStorage<'a, Focusable>,
+        WriteStorage<'a, Renderable>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            style,
+            mut ui_events,
+            buttons,
+            ui_rects,
+            hovereds,
+            focuseds,
+            presseds,
+            clickeds,
+            mut pickables,
+            mut focusables,
+            mut renderables,
+        ) = data;
+
+        for (entity, _) in (&*entities, &buttons).join() {
+            let _ = pickables.insert(entity, Pickable);
+
+            if !focusables.contains(entity) {
+                let _ = focusables.insert(entity, Focusable::default());
+            }
+
+            if clickeds.contains(entity) {
+                ui_events.single_write(UiEvent::Clicked(entity));
+            }
+
+            if let Some(rect) = laid_out_rect(ui_rects.get(entity)) {
+                let (w, h) = (rect.w, rect.h);
+                let mut mesh_builder = MeshBuilder::new();
+
+                rectangle(
+                    &mut mesh_builder,
+                    0.0,
+                    0.0,
+                    w,
+                    h,
+                    style.background(
+                        hovereds.contains(entity) || focuseds.contains(entity),
+                        presseds.contains(entity),
+                    ),
+                );
+
+                let _ = renderables.insert(entity, mesh_renderable(mesh_builder));
+            }
+        }
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.register::<Button>();
+
+    dispatcher_builder.add(ButtonSystem, "button", &["picking", "focus_navigation"]);
+}
@@ -0,0 +1,207 @@
+use crate::{
+    graphics::ui::layout::UiRect,
+    input::{picking::Clicked, GamepadButtons, Keys},
+};
+use ggez::{
+    event::Button,
+    input::keyboard::KeyCode,
+    nalgebra::{Point2, Vector2},
+    Context,
+};
+use specs::prelude::*;
+
+/// Lets the entity be focused with the keyboard or a gamepad.
+/// Tab goes through focusable entities by `order`, then by when they were created.
+/// The arrow keys and d-pad move to the closest one in that direction
+#[derive(Default)]
+pub struct Focusable {
+    pub order: i32,
+}
+
+impl Component for Focusable {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Added to the one entity that has focus, pressing enter or the south button clicks it
+#[derive(Default)]
+pub struct Focused;
+
+impl Component for Focused {
+    type Storage = NullStorage<Self>;
+}
+
+/// For focusable widgets that use left and right themselves, like sliders,
+/// so those don't move focus away while the widget has it
+#[derive(Default)]
+pub struct UsesLeftRight;
+
+impl Component for UsesLeftRight {
+    type Storage = NullStorage<Self>;
+}
+
+#[derive(Default)]
+pub struct FocusNavigation {
+    focused: Option<Entity>,
+}
+
+impl FocusNavigation {
+    fn tab_order(entities: &Entities, focusables: &ReadStorage<Focusable>) -> Vec<Entity> {
+        let mut tab_order: Vec<(i32, Entity)> = (&**entities, focusables)
+            .join()
+            .map(|(entity, focusable)| (focusable.order, entity))
+            .collect();
+
+        tab_order.sort_by_key(|(order, entity)| (*order, entity.id()));
+        tab_order.into_iter().map(|(_, entity)| entity).collect()
+    }
+
+    /// The closest entity in `direction` from the focused one, going by the centers
+    /// of their rects. Entities off to the side count as further away
+    fn closest_in_direction(
+        focused: Entity,
+        direction: Vector2<f32>,
+        entities: &Entities,
+        focusables: &ReadStorage<Focusable>,
+        ui_rects: &ReadStorage<UiRect>,
+    ) -> Option<Entity> {
+        let center = |ui_rect: &UiRect| {
+            Point2::new(
+                ui_rect.0.x + ui_rect.0.w / 2.0,
+                ui_rect.0.y + ui_rect.0.h / 2.0,
+            )
+        };
+        let from = center(ui_rects.get(focused)?);
+
+        (&**entities, focusables, ui_rects)
+            .join()
+            .filter(|(entity, _, _)| *entity != focused)
+            .filter_map(|(entity, _, ui_rect)| {
+                let offset = center(ui_rect) - from;
+                let along = offset.dot(&direction);
+                let across = (offset - direction * along).norm();
+
+                if along > 0.0 {
+                    Some((entity, along + across * 2.0))
+                } else {
+                    None
+                }
+            })
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(entity, _)| entity)
+    }
+}
+
+impl<'a> System<'a> for FocusNavigation {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Keys>,
+        Read<'a, GamepadButtons>,
+        ReadStorage<'a, Focusable>,
+        ReadStorage<'a, UsesLeftRight>,
+        ReadStorage<'a, UiRect>,
+        WriteStorage<'a, Focused>,
+        WriteStorage<'a, Clicked>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            keys,
+            gamepad_buttons,
+            focusables,
+            uses_left_rights,
+            ui_rects,
+            mut focuseds,
+            mut clickeds,
+        ) = data;
+
+        if let Some(focused) = self.focused {
+            if !entities.is_alive(focused) || !focusables.contains(focused) {
+                self.focused = None;
+            }
+        }
+
+        // Clicking something focusable focuses it, so the keyboard carries on from there
+        if let Some((entity, _, _)) = (&*entities, &focusables, &clickeds).join().next() {
+            self.focused = Some(entity);
+        }
+
+        if keys.is_pressed(&KeyCode::Tab) {
+            let tab_order = Self::tab_order(&entities, &focusables);
+            let backwards = keys.is_down(&KeyCode::LShift) || keys.is_down(&KeyCode::RShift);
+
+            self.focused = match self
+                .focused
+                .and_then(|focused| tab_order.iter().position(|entity| *entity == focused))
+            {
+                Some(index) if backwards => {
+                    Some(tab_order[(index + tab_order.len() - 1) % tab_order.len()])
+                }
+                Some(index) => Some(tab_order[(index + 1) % tab_order.len()]),
+                None if backwards => tab_order.last().cloned(),
+                None => tab_order.first().cloned(),
+            };
+        }
+
+        let uses_left_right = self
+            .focused
+            .map_or(false, |focused| uses_left_rights.contains(focused));
+
+        let direction = if keys.is_pressed(&KeyCode::Up)
+            || gamepad_buttons.is_pressed(&Button::DPadUp)
+        {
+            Some(Vector2::new(0.0, -1.0))
+        } else if keys.is_pressed(&KeyCode::Down) || gamepad_buttons.is_pressed(&Button::DPadDown) {
+            Some(Vector2::new(0.0, 1.0))
+        } else if uses_left_right {
+            None
+        } else if keys.is_pressed(&KeyCode::Left) || gamepad_buttons.is_pressed(&Button::DPadLeft) {
+            Some(Vector2::new(-1.0, 0.0))
+        } else if keys.is_pressed(&KeyCode::Right) || gamepad_buttons.is_pressed(&Button::DPadRight)
+        {
+            Some(Vector2::new(1.0, 0.0))
+        } else {
+            None
+        };
+
+        if let Some(direction) = direction {
+            self.focused = match self.focused {
+                Some(focused) => Self::closest_in_direction(
+                    focused,
+                    direction,
+                    &entities,
+                    &focusables,
+                    &ui_rects,
+                )
+                .or(Some(focused)),
+                None => Self::tab_order(&entities, &focusables).first().cloned(),
+            };
+        }
+
+        focuseds.clear();
+
+        if let Some(focused) = self.focused {
+            let _ = focuseds.insert(focused, Focused);
+
+            if keys.is_pressed(&KeyCode::Return)
+                || keys.is_pressed(&KeyCode::NumpadEnter)
+                || gamepad_buttons.is_pressed(&Button::South)
+            {
+                let _ = clickeds.insert(focused, Clicked);
+            }
+        }
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.register::<Focusable>();
+    world.register::<Focused>();
+    world.register::<UsesLeftRight>();
+
+    dispatcher_builder.add(FocusNavigation::default(), "focus_navigation", &["picking"]);
+}
@@ -0,0 +1,121 @@
+pub mod button;
+pub mod focus;
+pub mod progress_bar;
+pub mod scroll_view;
+pub mod slider;
+pub mod toggle;
+pub mod window;
+
+use crate::{
+    graphics::{
+        rendering::{RenderInstruction, Renderable},
+        ui::layout::UiRect,
+    },
+    plugin::Plugin,
+};
+use ggez::{
+    graphics::{Color, DrawMode, MeshBuilder, Rect},
+    Context, GameResult,
+};
+use smart_default::SmartDefault;
+use specs::{prelude::*, shrev::EventChannel};
+
+/// Sent when the user interacts with a widget
+#[derive(Clone, Debug)]
+pub enum UiEvent {
+    Clicked(Entity),
+    Toggled {
+        entity: Entity,
+        checked: bool,
+    },
+    SliderChanged {
+        entity: Entity,
+        value: f32,
+    },
+    /// A `UiWindow` was closed
+    Closed(Entity),
+}
+
+pub type UiEvents = EventChannel<UiEvent>;
+
+/// The colors every widget is drawn with
+#[derive(Clone, SmartDefault)]
+pub struct WidgetStyle {
+    #[default(Color::new(0.3, 0.3, 0.3, 1.0))]
+    pub background: Color,
+    #[default(Color::new(0.4, 0.4, 0.4, 1.0))]
+    pub hovered: Color,
+    #[default(Color::new(0.2, 0.2, 0.2, 1.0))]
+    pub pressed: Color,
+    /// Used for the fill of progress bars, slider handles and checked toggles
+    #[default(Color::new(0.3, 0.6, 0.9, 1.0))]
+    pub accent: Color,
+}
+
+impl WidgetStyle {
+    fn background(&self, hovered: bool, pressed: bool) -> Color {
+        if pressed {
+            self.pressed
+        } else if hovered {
+            self.hovered
+        } else {
+            self.background
+        }
+    }
+}
+
+/// Where the widget was laid out, if it has any area to draw in.
+/// Widgets are drawn relative to their top left corner and the layout decides where that is
+fn laid_out_rect(ui_rect: Option<&UiRect>) -> Option<Rect> {
+    ui_rect
+        .map(|ui_rect| ui_rect.0)
+        .filter(|rect| rect.w > 0.0 && rect.h > 0.0)
+}
+
+fn rectangle(mesh_builder: &mut MeshBuilder, x: f32, y: f32, w: f32, h: f32, color: Color) {
+    // ggez refuses to build meshes out of degenerate rects
+    if w > 0.0 && h > 0.0 {
+        mesh_builder.rectangle(DrawMode::fill(), Rect::new(x, y, w, h), color);
+    }
+}
+
+fn mesh_renderable(mesh_builder: MeshBuilder) -> Renderable {
+    Renderable {
+        instruction: RenderInstruction::Mesh(mesh_builder),
+        draw_param: None,
+    }
+}
+
+pub fn setup<'a, 'b>(
+    ctx: &mut Context,
+    world: &mut World,
+    dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.add_resource(WidgetStyle::default());
+    world.add_resource(UiEvents::new());
+
+    focus::setup(ctx, world, dispatcher_builder);
+    button::setup(ctx, world, dispatcher_builder);
+    progress_bar::setup(ctx, world, dispatcher_builder);
+    scroll_view::setup(ctx, world, dispatcher_builder);
+    slider::setup(ctx, world, dispatcher_builder);
+    toggle::setup(ctx, world, dispatcher_builder);
+    window::setup(ctx, world, dispatcher_builder);
+}
+
+/// Buttons, toggles, sliders, progress bars, scroll views, windows and keyboard focus.
+/// Widgets are sized by the UI layout, so give them a `UiSize` that isn't `Auto`
+pub struct WidgetsPlugin;
+
+impl<'a, 'b> Plugin<'a, 'b> for WidgetsPlugin {
+    fn build(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+    ) -> GameResult {
+        setup(ctx, world, dispatcher_builder);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use super::{laid_out_rect, mesh_renderable, rectangle, WidgetStyle};
+use crate::graphics::{rendering::Renderable, ui::layout::UiRect};
+use ggez::{graphics::MeshBuilder, Context};
+use specs::prelude::*;
+
+#[derive(Default)]
+pub struct ProgressBar {
+    /// From 0.0 to 1.0
+    pub progress: f32,
+}
+
+impl Component for ProgressBar {
+    type Storage = DenseVecStorage<Self>;
+}
+
+pub struct ProgressBarRenderer;
+
+impl<'a> System<'a> for ProgressBarRenderer {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, WidgetStyle>,
+        ReadStorage<'a, ProgressBar>,
+        ReadStorage<'a, UiRect>,
+        WriteStorage<'a, Renderable>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, style, progress_bars, ui_rects, mut renderables) = data;
+
+        for (entity, progress_bar) in (&*entities, &progress_bars).join() {
+            if let Some(rect) = laid_out_rect(ui_rects.get(entity)) {
+                let (w, h) = (rect.w, rect.h);
+                let progress = progress_bar.progress.max(0.0).min(1.0);
+                let mut mesh_builder = MeshBuilder::new();
+
+                rectangle(&mut mesh_builder, 0.0, 0.0, w, h, style.background);
+                rectangle(&mut mesh_builder, 0.0, 0.0, w * progress, h, style.accent);
+
+                let _ = renderables.insert(entity, mesh_renderable(mesh_builder));
+            }
+        }
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.register::<ProgressBar>();
+
+    dispatcher_builder.add(ProgressBarRenderer, "progress_bar_renderer", &[]);
+}
@@ -0,0 +1,342 @@
+use super::{mesh_renderable, rectangle, WidgetStyle};
+use crate::{
+    graphics::{
+        rendering::Renderable,
+        ui::layout::{ContentSize, Direction, Edges, UiContainer, UiRect},
+    },
+    input::{
+        picking::{Hovered, Pickable},
+        MouseButtons, MouseMotion, MousePosition, MouseWheel,
+    },
+};
+use ggez::{
+    graphics::{Color, MeshBuilder, Rect},
+    input::mouse::MouseButton,
+    nalgebra::Vector2,
+    Context,
+};
+use smart_default::SmartDefault;
+use specs::prelude::*;
+
+static SCROLLBAR_WIDTH: f32 = 8.0;
+static MIN_THUMB_SIZE: f32 = 16.0;
+
+/// Scrolls its children vertically, and horizontally if they're wider than it.
+/// The children go in through `UiParent` and are clipped to the scroll view.
+/// A stacking `UiContainer` is added if it doesn't have one, which is what lists want
+#[derive(SmartDefault)]
+pub struct ScrollView {
+    /// Pixels scrolled for each step of the mouse wheel
+    #[default = 30.0]
+    pub wheel_speed: f32,
+    /// Lets the content be dragged around with the left mouse button,
+    /// by anything in it that isn't pickable itself
+    #[default = true]
+    pub drag: bool,
+}
+
+impl Component for ScrollView {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Grab {
+    Content,
+    Scrollbar,
+}
+
+/// The thumb of the vertical scrollbar, relative to the top left of the scroll view,
+/// if the content doesn't fit
+fn scrollbar_thumb(rect: Rect, view: Rect, content: Vector2<f32>, scroll: f32) -> Option<Rect> {
+    if content.y <= view.h {
+        return None;
+    }
+
+    let height = (rect.h * view.h / content.y)
+        .max(MIN_THUMB_SIZE)
+        .min(rect.h);
+    let y = (rect.h - height) * scroll / (content.y - view.h);
+
+    Some(Rect::new(
+        rect.w - SCROLLBAR_WIDTH,
+        y,
+        SCROLLBAR_WIDTH,
+        height,
+    ))
+}
+
+#[derive(Default)]
+pub struct ScrollViewSystem {
+    grabbed: Option<(Entity, Grab)>,
+}
+
+impl<'a> System<'a> for ScrollViewSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, MousePosition>,
+        Read<'a, MouseMotion>,
+        Read<'a, MouseWheel>,
+        Read<'a, MouseButtons>,
+        Read<'a, WidgetStyle>,
+        ReadStorage<'a, ScrollView>,
+        ReadStorage<'a, UiRect>,
+        ReadStorage<'a, ContentSize>,
+        ReadStorage<'a, Hovered>,
+        WriteStorage<'a, UiContainer>,
+        WriteStorage<'a, Pickable>,
+        WriteStorage<'a, Renderable>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mouse_position,
+            mouse_motion,
+            mouse_wheel,
+            mouse_buttons,
+            style,
+            scroll_views,
+            ui_rects,
+            content_sizes,
+            hovereds,
+            mut containers,
+            mut pickables,
+            mut renderables,
+        ) = data;
+
+        let mouse_position = mouse_position.0;
+
+        // The smallest scroll view under the mouse, which is the innermost one when they're nested
+        let hovered = (&*entities, &scroll_views, &ui_rects)
+            .join()
+            .filter(|(_, _, ui_rect)| ui_rect.0.contains(mouse_position))
+            .min_by(|(_, _, a), (_, _, b)| {
+                (a.0.w * a.0.h)
+                    .partial_cmp(&(b.0.w * b.0.h))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map(|(entity, _, _)| entity);
+
+        if !mouse_buttons.is_down(&MouseButton::Left) {
+            self.grabbed = None;
+        }
+
+        for (entity, scroll_view) in (&*entities, &scroll_views).join() {
+            let _ = pickables.insert(entity, Pickable);
+
+            let container = containers.entry(entity).ok().map(|entry| {
+                entry.or_insert_with(|| UiContainer {
+                    direction: Some(Direction::Vertical),
+                    padding: Edges {
+                        right: SCROLLBAR_WIDTH,
+                        ..Edges::default()
+                    },
+                    ..Default::default()
+                })
+            });
+
+            if let Some(container) = container {
+                container.clip = true;
+
+                if let Some(ui_rect) = ui_rects.get(entity) {
+                    let rect = ui_rect.0;
+                    let view = container.padding.shrink(rect);
+                    let content = content_sizes
+                        .get(entity)
+                        .map_or(Vector2::zeros(), |content_size| content_size.0);
+
+                    if hovered == Some(entity) {
+                        if let Some(mouse_wheel) = mouse_wheel.0 {
+                            container.scroll -= mouse_wheel * scroll_view.wheel_speed;
+                        }
+
+                        if mouse_buttons.is_pressed(&MouseButton::Left) {
+                            let on_scrollbar =
+                                scrollbar_thumb(rect, view, content, container.scroll.y).is_some()
+                                    && mouse_position.x >= rect.x + rect.w - SCROLLBAR_WIDTH;
+
+                            if on_scrollbar {
+                                self.grabbed = Some((entity, Grab::Scrollbar));
+                            } else if scroll_view.drag && hovereds.contains(entity) {
+                                self.grabbed = Some((entity, Grab::Content));
+                            }
+                        }
+                    }
+
+                    if let (Some((grabbed, grab)), Some(mouse_motion)) =
+                        (self.grabbed, mouse_motion.0)
+                    {
+                        if grabbed == entity {
+                            match grab {
+                                Grab::Content => container.scroll -= mouse_motion,
+                                Grab::Scrollbar => {
+                                    if let Some(thumb) =
+                                        scrollbar_thumb(rect, view, content, container.scroll.y)
+                                    {
+                                        let track = rect.h - thumb.h;
+
+                                        if track > 0.0 {
+                                            container.scroll.y +=
+                                                mouse_motion.y * (content.y - view.h) / track;
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    container.scroll.x = container.scroll.x.min(content.x - view.w).max(0.0);
+                    container.scroll.y = container.scroll.y.min(content.y - view.h).max(0.0);
+
+                    let mut mesh_builder = MeshBuilder::new();
+
+                    // Covers the whole scroll view so the mesh lines up with its rect,
+                    // which keeps its bounds, and so hovering and dragging, over the content
+                    rectangle(
+                        &mut mesh_builder,
+                        0.0,
+                        0.0,
+                        rect.w,
+                        rect.h,
+                        Color::new(0.0, 0.0, 0.0, 0.0),
+                    );
+
+                    if let Some(thumb) = scrollbar_thumb(rect, view, content, container.scroll.y) {
+                        rectangle(
+                            &mut mesh_builder,
+                            rect.w - SCROLLBAR_WIDTH,
+                            0.0,
+                            SCROLLBAR_WIDTH,
+                            rect.h,
+                            style.background,
+                        );
+                        rectangle(
+                            &mut mesh_builder,
+                            thumb.x,
+                            thumb.y,
+                            thumb.w,
+                            thumb.h,
+                            style.accent,
+                        );
+                    }
+
+                    // An empty mesh can't be built, and there's nothing to show anyway
+                    if rect.w > 0.0 && rect.h > 0.0 {
+                        let _ = renderables.insert(entity, mesh_renderable(mesh_builder));
+                    } else {
+                        renderables.remove(entity);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.register::<ScrollView>();
+
+    dispatcher_builder.add(ScrollViewSystem::default(), "scroll_view", &["picking"]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::{
+        rendering::Bounds,
+        ui::{
+            layout::{ClipRect, Size, UiLayout, UiParent, UiSize},
+            Hidden, UiElement,
+        },
+        Anchor, Position, ScreenSize,
+    };
+    use ggez::nalgebra::Point2;
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.register::<ScrollView>();
+        world.register::<UiElement>();
+        world.register::<Hidden>();
+        world.register::<UiSize>();
+        world.register::<UiParent>();
+        world.register::<UiContainer>();
+        world.register::<UiRect>();
+        world.register::<ClipRect>();
+        world.register::<ContentSize>();
+        world.register::<Bounds>();
+        world.register::<Position>();
+        world.register::<Hovered>();
+        world.register::<Pickable>();
+        world.register::<Renderable>();
+        world.add_resource(ScreenSize(Vector2::new(200.0, 100.0)));
+        world.add_resource(MousePosition(Point2::new(50.0, 50.0)));
+        world.add_resource(MouseMotion::default());
+        world.add_resource(MouseWheel::default());
+        world.add_resource(MouseButtons::default());
+        world.add_resource(WidgetStyle::default());
+        world
+    }
+
+    fn fixed(width: f32, height: f32) -> UiSize {
+        UiSize {
+            width: Size::Fixed(width),
+            height: Size::Fixed(height),
+        }
+    }
+
+    fn update(world: &World, scroll_view_system: &mut ScrollViewSystem) {
+        scroll_view_system.run_now(&world.res);
+        UiLayout.run_now(&world.res);
+    }
+
+    #[test]
+    fn clips_to_a_view_shorter_than_the_screen() {
+        let mut world = world();
+        let scroll_view = world
+            .create_entity()
+            .with(ScrollView::default())
+            .with(UiElement {
+                anchor: Some(Anchor::TopLeft),
+                origin: Some(Anchor::TopLeft),
+                offset: Vector2::new(0.0, 20.0),
+                ..Default::default()
+            })
+            .with(fixed(100.0, 60.0))
+            .build();
+        let items: Vec<Entity> = (0..5)
+            .map(|_| {
+                world
+                    .create_entity()
+                    .with(UiElement::default())
+                    .with(fixed(50.0, 40.0))
+                    .with(UiParent(scroll_view))
+                    .build()
+            })
+            .collect();
+
+        let mut scroll_view_system = ScrollViewSystem::default();
+        update(&world, &mut scroll_view_system);
+
+        world.write_resource::<MouseWheel>().0 = Some(Vector2::new(0.0, -1.0));
+        update(&world, &mut scroll_view_system);
+
+        let view = Rect::new(0.0, 20.0, 100.0 - SCROLLBAR_WIDTH, 60.0);
+        let clip_rects = world.read_storage::<ClipRect>();
+        let ui_rects = world.read_storage::<UiRect>();
+
+        for item in &items {
+            assert_eq!(
+                clip_rects.get(*item).map(|clip_rect| clip_rect.0),
+                Some(view)
+            );
+        }
+
+        let wheel_speed = ScrollView::default().wheel_speed;
+        assert_eq!(ui_rects.get(items[0]).unwrap().0.y, 20.0 - wheel_speed);
+        assert!(world.read_storage::<Renderable>().contains(scroll_view));
+    }
+}
@@ -0,0 +1,165 @@
+use super::{
+    focus::{Focusable, Focused, UsesLeftRight},
+    laid_out_rect, mesh_renderable, rectangle, UiEvent, UiEvents, WidgetStyle,
+};
+use crate::{
+    graphics::{rendering::Renderable, ui::layout::UiRect},
+    input::{
+        picking::{Hovered, Pickable, Pressed},
+        GamepadButtons, Keys, MousePosition,
+    },
+};
+use ggez::{event::Button, graphics::MeshBuilder, input::keyboard::KeyCode, Context};
+use smart_default::SmartDefault;
+use specs::prelude::*;
+
+/// A horizontal slider between `min` and `max`, dragged with the mouse or moved
+/// with left and right while focused. Sends `UiEvent::SliderChanged` whenever the value changes
+#[derive(SmartDefault)]
+pub struct Slider {
+    pub value: f32,
+    pub min: f32,
+    #[default = 1.0]
+    pub max: f32,
+    /// How far left and right move the value, as a fraction of the range
+    #[default = 0.1]
+    pub step: f32,
+}
+
+impl Slider {
+    /// How far along the slider the value is, from 0.0 to 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.max > self.min {
+            ((self.value - self.min) / (self.max - self.min))
+                .max(0.0)
+                .min(1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Component for Slider {
+    type Storage = DenseVecStorage<Self>;
+}
+
+pub struct SliderSystem;
+
+impl<'a> System<'a> for SliderSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, MousePosition>,
+        Read<'a, Keys>,
+        Read<'a, GamepadButtons>,
+        Read<'a, WidgetStyle>,
+        Write<'a, UiEvents>,
+        WriteStorage<'a, Slider>,
+        ReadStorage<'a, UiRect>,
+        ReadStorage<'a, Hovered>,
+        ReadStorage<'a, Focused>,
+        ReadStorage<'a, Pressed>,
+        WriteStorage<'a, Pickable>,
+        WriteStorage<'a, Focusable>,
+        WriteStorage<'a, UsesLeftRight>,
+        WriteStorage<'a, Renderable>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mouse_position,
+            keys,
+            gamepad_buttons,
+            style,
+            mut ui_events,
+            mut sliders,
+            ui_rects,
+            hovereds,
+            focuseds,
+            presseds,
+            mut pickables,
+            mut focusables,
+            mut uses_left_rights,
+            mut renderables,
+        ) = data;
+
+        for (entity, slider) in (&*entities, &mut sliders).join() {
+            let _ = pickables.insert(entity, Pickable);
+            let _ = uses_left_rights.insert(entity, UsesLeftRight);
+
+            if !focusables.contains(entity) {
+                let _ = focusables.insert(entity, Focusable::default());
+            }
+
+            if let Some(rect) = laid_out_rect(ui_rects.get(entity)) {
+                let (w, h) = (rect.w, rect.h);
+                let pressed = presseds.contains(entity);
+
+                let fraction = if pressed {
+                    Some((mouse_position.0.x - rect.x) / w)
+                } else if focuseds.contains(entity) {
+                    if keys.is_pressed(&KeyCode::Left)
+                        || gamepad_buttons.is_pressed(&Button::DPadLeft)
+                    {
+                        Some(slider.fraction() - slider.step)
+                    } else if keys.is_pressed(&KeyCode::Right)
+                        || gamepad_buttons.is_pressed(&Button::DPadRight)
+                    {
+                        Some(slider.fraction() + slider.step)
+                    } else {
+                        None
+                    }
+                } else {
+                    None
+                };
+
+                if let Some(fraction) = fraction {
+                    let fraction = fraction.max(0.0).min(1.0);
+                    let value = slider.min + fraction * (slider.max - slider.min);
+
+                    if (value - slider.value).abs() > std::f32::EPSILON {
+                        slider.value = value;
+                        ui_events.single_write(UiEvent::SliderChanged { entity, value });
+                    }
+                }
+
+                let handle_width = h.min(w);
+                let handle_x = slider.fraction() * (w - handle_width);
+                let mut mesh_builder = MeshBuilder::new();
+
+                rectangle(
+                    &mut mesh_builder,
+                    0.0,
+                    h / 3.0,
+                    w,
+                    h / 3.0,
+                    style.background(
+                        hovereds.contains(entity) || focuseds.contains(entity),
+                        pressed,
+                    ),
+                );
+                rectangle(
+                    &mut mesh_builder,
+                    handle_x,
+                    0.0,
+                    handle_width,
+                    h,
+                    style.accent,
+                );
+
+                let _ = renderables.insert(entity, mesh_renderable(mesh_builder));
+            }
+        }
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.register::<Slider>();
+
+    dispatcher_builder.add(SliderSystem, "slider", &["picking", "focus_navigation"]);
+}
@@ -0,0 +1,115 @@
+use super::{
+    focus::{Focusable, Focused},
+    laid_out_rect, mesh_renderable, rectangle, UiEvent, UiEvents, WidgetStyle,
+};
+use crate::{
+    graphics::{rendering::Renderable, ui::layout::UiRect},
+    input::picking::{Clicked, Hovered, Pickable, Pressed},
+};
+use ggez::{graphics::MeshBuilder, Context};
+use specs::prelude::*;
+
+/// A checkbox, flips `checked` and sends `UiEvent::Toggled` when clicked
+#[derive(Default)]
+pub struct Toggle {
+    pub checked: bool,
+}
+
+impl Component for Toggle {
+    type Storage = DenseVecStorage<Self>;
+}
+
+pub struct ToggleSystem;
+
+impl<'a> System<'a> for ToggleSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, WidgetStyle>,
+        Write<'a, UiEvents>,
+        WriteStorage<'a, Toggle>,
+        ReadStorage<'a, UiRect>,
+        ReadStorage<'a, Hovered>,
+        ReadStorage<'a, Focused>,
+        ReadStorage<'a, Pressed>,
+        ReadStorage<'a, Clicked>,
+        WriteStorage<'a, Pickable>,
+        WriteStorage<'a, Focusable>,
+        WriteStorage<'a, Renderable>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            style,
+            mut ui_events,
+            mut toggles,
+            ui_rects,
+            hovereds,
+            focuseds,
+            presseds,
+            clickeds,
+            mut pickables,
+            mut focusables,
+            mut renderables,
+        ) = data;
+
+        for (entity, toggle) in (&*entities, &mut toggles).join() {
+            let _ = pickables.insert(entity, Pickable);
+
+            if !focusables.contains(entity) {
+                let _ = focusables.insert(entity, Focusable::default());
+            }
+
+            if clickeds.contains(entity) {
+                toggle.checked = !toggle.checked;
+                ui_events.single_write(UiEvent::Toggled {
+                    entity,
+                    checked: toggle.checked,
+                });
+            }
+
+            if let Some(rect) = laid_out_rect(ui_rects.get(entity)) {
+                let (w, h) = (rect.w, rect.h);
+                let mut mesh_builder = MeshBuilder::new();
+
+                rectangle(
+                    &mut mesh_builder,
+                    0.0,
+                    0.0,
+                    w,
+                    h,
+                    style.background(
+                        hovereds.contains(entity) || focuseds.contains(entity),
+                        presseds.contains(entity),
+                    ),
+                );
+
+                if toggle.checked {
+                    let inset = w.min(h) / 4.0;
+
+                    rectangle(
+                        &mut mesh_builder,
+                        inset,
+                        inset,
+                        w - inset * 2.0,
+                        h - inset * 2.0,
+                        style.accent,
+                    );
+                }
+
+                let _ = renderables.insert(entity, mesh_renderable(mesh_builder));
+            }
+        }
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.register::<Toggle>();
+
+    dispatcher_builder.add(ToggleSystem, "toggle", &["picking", "focus_navigation"]);
+}
@@ -0,0 +1,396 @@
+use super::{mesh_renderable, rectangle, UiEvent, UiEvents, WidgetStyle};
+use crate::{
+    graphics::{
+        rendering::Renderable,
+        text::Overflow,
+        ui::{
+            layout::{Edges, Size, UiContainer, UiParent, UiRect, UiSize},
+            Hidden, UiElement,
+        },
+        Anchor, Layer, Position,
+    },
+    input::{picking::Pickable, MouseButtons, MouseMotion, MousePosition},
+    renderers::text::Text,
+};
+use ggez::{
+    graphics::{MeshBuilder, Scale},
+    input::mouse::MouseButton,
+    nalgebra::{Point2, Vector2},
+    Context,
+};
+use specs::prelude::*;
+use std::collections::HashMap;
+
+static TITLE_HEIGHT: f32 = 24.0;
+static TITLE_FONT_SIZE: f32 = 16.0;
+static PADDING: f32 = 5.0;
+static GRIP_SIZE: f32 = 12.0;
+static MIN_SIZE: f32 = 48.0;
+static DEFAULT_SIZE: (f32, f32) = (240.0, 180.0);
+/// Windows are drawn above everything else on layers this far apart,
+/// leaving room in between for what's inside them
+static WINDOW_LAYER: i32 = 100;
+static WINDOW_LAYER_STRIDE: i32 = 100;
+
+/// A window for in-game tools with a title bar to drag it around by.
+/// It's placed with `Position` from the top left of the screen and sized with `UiSize`.
+/// Anything put inside it with `UiParent` is clipped to the area under the title bar,
+/// and has its `Layer` managed so it stays on top of the window.
+/// Closing a window adds `Hidden` to it, remove that to open it again
+#[derive(Default)]
+pub struct UiWindow {
+    pub title: String,
+    pub resizable: bool,
+    pub closable: bool,
+}
+
+impl Component for UiWindow {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Grab {
+    Move,
+    Resize,
+}
+
+fn fixed_or(size: Size, laid_out: f32) -> f32 {
+    match size {
+        Size::Fixed(size) => size,
+        _ => laid_out,
+    }
+}
+
+#[derive(Default)]
+pub struct UiWindowSystem {
+    /// Bottom to top
+    order: Vec<Entity>,
+    titles: HashMap<Entity, Entity>,
+    grabbed: Option<(Entity, Grab)>,
+}
+
+impl<'a> System<'a> for UiWindowSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, MousePosition>,
+        Read<'a, MouseMotion>,
+        Read<'a, MouseButtons>,
+        Read<'a, WidgetStyle>,
+        Write<'a, UiEvents>,
+        ReadStorage<'a, UiWindow>,
+        ReadStorage<'a, UiRect>,
+        ReadStorage<'a, UiParent>,
+        WriteStorage<'a, Hidden>,
+        WriteStorage<'a, UiElement>,
+        WriteStorage<'a, UiContainer>,
+        WriteStorage<'a, UiSize>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, Layer>,
+        WriteStorage<'a, Text>,
+        WriteStorage<'a, Pickable>,
+        WriteStorage<'a, Renderable>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mouse_position,
+            mouse_motion,
+            mouse_buttons,
+            style,
+            mut ui_events,
+            windows,
+            ui_rects,
+            parents,
+            mut hiddens,
+            mut ui_elements,
+            mut containers,
+            mut sizes,
+            mut positions,
+            mut layers,
+            mut texts,
+            mut pickables,
+            mut renderables,
+        ) = data;
+
+        self.order
+            .retain(|window| entities.is_alive(*window) && windows.contains(*window));
+
+        for (window, _) in (&*entities, &windows).join() {
+            if !self.order.contains(&window) {
+                self.order.push(window);
+            }
+        }
+
+        let order = &self.order;
+        self.titles.retain(|window, title| {
+            let keep = order.contains(window);
+
+            if !keep {
+                let _ = entities.delete(*title);
+            }
+
+            keep
+        });
+
+        for (window, ui_window) in (&*entities, &windows).join() {
+            if !ui_elements.contains(window) {
+                let _ = ui_elements.insert(
+                    window,
+                    UiElement {
+                        anchor: Some(Anchor::TopLeft),
+                        origin: Some(Anchor::TopLeft),
+                        ..Default::default()
+                    },
+                );
+            }
+
+            if !containers.contains(window) {
+                let _ = containers.insert(
+                    window,
+                    UiContainer {
+                        padding: Edges {
+                            top: TITLE_HEIGHT + PADDING,
+                            ..Edges::uniform(PADDING)
+                        },
+                        clip: true,
+                        ..Default::default()
+                    },
+                );
+            }
+
+            if !sizes.contains(window) {
+                let _ = sizes.insert(
+                    window,
+                    UiSize {
+                        width: Size::Fixed(DEFAULT_SIZE.0),
+                        height: Size::Fixed(DEFAULT_SIZE.1),
+                    },
+                );
+            }
+
+            if !positions.contains(window) {
+                let _ = positions.insert(window, Position(Point2::new(0.0, 0.0)));
+            }
+
+            let _ = pickables.insert(window, Pickable);
+
+            if !self.titles.contains_key(&window) {
+                let title = entities.create();
+
+                let _ = texts.insert(
+                    title,
+                    Text {
+                        text: ui_window.title.clone(),
+                        scale: Scale::uniform(TITLE_FONT_SIZE),
+                        overflow: Overflow::Ellipsis,
+                        ..Default::default()
+                    },
+                );
+                let _ = ui_elements.insert(
+                    title,
+                    UiElement {
+                        anchor: Some(Anchor::TopLeft),
+                        origin: Some(Anchor::TopLeft),
+                        ..Default::default()
+                    },
+                );
+
+                self.titles.insert(window, title);
+            }
+        }
+
+        if mouse_buttons.is_pressed(&MouseButton::Left) {
+            let clicked = self.order.iter().rev().cloned().find(|window| {
+                !hiddens.contains(*window)
+                    && ui_rects
+                        .get(*window)
+                        .map_or(false, |ui_rect| ui_rect.0.contains(mouse_position.0))
+            });
+
+            if let Some(window) = clicked {
+                // Bring it to the top
+                self.order.retain(|other| *other != window);
+                self.order.push(window);
+
+                if let (Some(ui_window), Some(ui_rect)) =
+                    (windows.get(window), ui_rects.get(window))
+                {
+                    let local = mouse_position.0 - Point2::from(ui_rect.0.point());
+                    let (w, h) = (ui_rect.0.w, ui_rect.0.h);
+
+                    if ui_window.closable && local.x >= w - TITLE_HEIGHT && local.y < TITLE_HEIGHT {
+                        let _ = hiddens.insert(window, Hidden);
+                        ui_events.single_write(UiEvent::Closed(window));
+                    } else if ui_window.resizable
+                        && local.x >= w - GRIP_SIZE
+                        && local.y >= h - GRIP_SIZE
+                    {
+                        self.grabbed = Some((window, Grab::Resize));
+                    } else if local.y < TITLE_HEIGHT {
+                        self.grabbed = Some((window, Grab::Move));
+                    }
+                }
+            }
+        }
+
+        if !mouse_buttons.is_down(&MouseButton::Left) {
+            self.grabbed = None;
+        }
+
+        if let (Some((window, grab)), Some(mouse_motion)) = (self.grabbed, mouse_motion.0) {
+            match grab {
+                Grab::Move => {
+                    if let Some(position) = positions.get_mut(window) {
+                        position.0 += mouse_motion;
+                    }
+                }
+                Grab::Resize => {
+                    if let Some(size) = sizes.get_mut(window) {
+                        let laid_out = ui_rects.get(window).map_or(Vector2::zeros(), |ui_rect| {
+                            Vector2::new(ui_rect.0.w, ui_rect.0.h)
+                        });
+
+                        size.width = Size::Fixed(
+                            (fixed_or(size.width, laid_out.x) + mouse_motion.x).max(MIN_SIZE),
+                        );
+                        size.height = Size::Fixed(
+                            (fixed_or(size.height, laid_out.y) + mouse_motion.y).max(MIN_SIZE),
+                        );
+                    }
+                }
+            }
+        }
+
+        for (index, window) in self.order.iter().enumerate() {
+            let layer = WINDOW_LAYER + index as i32 * WINDOW_LAYER_STRIDE;
+            let hidden = hiddens.contains(*window);
+            let (title, closable, resizable) =
+                windows
+                    .get(*window)
+                    .map_or((String::new(), false, false), |ui_window| {
+                        (
+                            ui_window.title.clone(),
+                            ui_window.closable,
+                            ui_window.resizable,
+                        )
+                    });
+            let _ = layers.insert(*window, Layer(layer));
+
+            let laid_out = ui_rects.get(*window).map(|ui_rect| ui_rect.0);
+            let (w, h) = sizes.get(*window).map_or((0.0, 0.0), |size| {
+                (
+                    fixed_or(size.width, laid_out.map_or(0.0, |rect| rect.w)),
+                    fixed_or(size.height, laid_out.map_or(0.0, |rect| rect.h)),
+                )
+            });
+
+            if let Some(title_entity) = self.titles.get(window) {
+                let _ = layers.insert(*title_entity, Layer(layer + 1));
+
+                if hidden {
+                    let _ = hiddens.insert(*title_entity, Hidden);
+                } else {
+                    hiddens.remove(*title_entity);
+                }
+
+                if let Some(text) = texts.get_mut(*title_entity) {
+                    let close_width = if closable { TITLE_HEIGHT } else { 0.0 };
+
+                    text.text = title;
+                    text.max_width = Some((w - close_width - PADDING * 2.0).max(0.0));
+                }
+
+                // Follows the window's own position so it doesn't lag a frame behind the layout
+                if let Some(position) = positions.get(*window).map(|position| position.0) {
+                    let _ = positions.insert(
+                        *title_entity,
+                        Position(
+                            position
+                                + Vector2::new(PADDING, (TITLE_HEIGHT - TITLE_FONT_SIZE) / 2.0),
+                        ),
+                    );
+                }
+            }
+
+            if w > 0.0 && h > 0.0 {
+                let grabbed = self.grabbed.map(|(grabbed, _)| grabbed) == Some(*window);
+                let mut mesh_builder = MeshBuilder::new();
+
+                rectangle(&mut mesh_builder, 0.0, 0.0, w, h, style.background);
+                rectangle(
+                    &mut mesh_builder,
+                    0.0,
+                    0.0,
+                    w,
+                    TITLE_HEIGHT.min(h),
+                    style.background(false, grabbed),
+                );
+
+                if closable {
+                    let inset = TITLE_HEIGHT / 4.0;
+
+                    rectangle(
+                        &mut mesh_builder,
+                        w - TITLE_HEIGHT + inset,
+                        inset,
+                        TITLE_HEIGHT - inset * 2.0,
+                        TITLE_HEIGHT - inset * 2.0,
+                        style.accent,
+                    );
+                }
+
+                if resizable {
+                    rectangle(
+                        &mut mesh_builder,
+                        w - GRIP_SIZE,
+                        h - GRIP_SIZE,
+                        GRIP_SIZE,
+                        GRIP_SIZE,
+                        style.hovered,
+                    );
+                }
+
+                let _ = renderables.insert(*window, mesh_renderable(mesh_builder));
+            }
+        }
+
+        // Keep everything inside a window drawn above it, deeper elements above shallower ones
+        let window_layers: HashMap<Entity, i32> = self
+            .order
+            .iter()
+            .enumerate()
+            .map(|(index, window)| (*window, WINDOW_LAYER + index as i32 * WINDOW_LAYER_STRIDE))
+            .collect();
+
+        for (entity, parent) in (&*entities, &parents).join() {
+            let mut ancestor = parent.0;
+            let mut depth = 1;
+
+            while depth < WINDOW_LAYER_STRIDE - 1 {
+                if let Some(window_layer) = window_layers.get(&ancestor) {
+                    let _ = layers.insert(entity, Layer(window_layer + 1 + depth));
+                    break;
+                }
+
+                if let Some(parent) = parents.get(ancestor) {
+                    ancestor = parent.0;
+                    depth += 1;
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.register::<UiWindow>();
+
+    dispatcher_builder.add(UiWindowSystem::default(), "ui_window", &["picking"]);
+}
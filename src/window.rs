@@ -0,0 +1,46 @@
+use ggez::{nalgebra::Vector2, Context};
+use smart_default::SmartDefault;
+use specs::{prelude::*, shrev::EventChannel};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowEvent {
+    FocusGained,
+    FocusLost,
+    /// ggez doesn't tell us about minimizing,
+    /// so this is sent when the window gets resized down to nothing
+    Minimized,
+    Restored,
+    Resized(Vector2<f32>),
+    /// Someone tried to close the window
+    QuitRequested,
+}
+
+pub type WindowEvents = EventChannel<WindowEvent>;
+
+/// The latest state of the window, for systems that would rather poll than read `WindowEvents`
+#[derive(SmartDefault)]
+pub struct WindowState {
+    #[default(true)]
+    pub focused: bool,
+    pub minimized: bool,
+}
+
+/// Systems set this to close the game, it gets checked after every update
+#[derive(Default)]
+pub struct QuitRequest(pub bool);
+
+/// While this is set closing the window gets cancelled, and only sends
+/// `WindowEvent::QuitRequested`. Systems can then save or ask first, and set `QuitRequest`
+#[derive(Default)]
+pub struct PreventQuit(pub bool);
+
+pub fn setup<'a, 'b>(
+    _ctx: &mut Context,
+    world: &mut World,
+    _dispatcher_builder: &mut DispatcherBuilder<'a, 'b>,
+) {
+    world.add_resource(WindowEvents::new());
+    world.add_resource(WindowState::default());
+    world.add_resource(QuitRequest::default());
+    world.add_resource(PreventQuit::default());
+}